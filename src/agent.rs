@@ -1,58 +1,95 @@
 pub use super::model::*;
 
+use std::collections::HashSet;
 use std::time::Instant;
-use rand;
 use rand::Rng;
-use rand::prelude::ThreadRng;
-use super::evaluation::ScoreParams;
+use rand::rngs::StdRng;
+use super::book;
+use super::bounds;
+use super::collections::LruSet;
+use super::config::AgentConfig;
+use super::evaluation;
+use super::evaluation::{ScoreParams, ScoreSheet};
 use super::mutations;
+use super::mutations::MutationStats;
 use super::rollouts;
 use super::rollouts::{Rollout, WorldState};
 
-const MAX_STRATEGY_GENERATION_MILLISECONDS: u128 = 90;
-
-const MUTATE_PROPORTION: f32 = 0.9;
-
-const MAX_MOVES_FROM_SCRATCH: i32 = 1;
-
 struct StrategyPool<'a> {
     strategy_id: i32,
     score_sheet: &'a Vec<ScoreParams>,
     best: Rollout,
+    best_rollout_index: usize,
+    num_rollouts: usize,
     entries: Vec<StrategyPoolEntry>,
+    simulated: LruSet<u64>, // fingerprints of strategies already rolled out
+    num_cache_hits: usize,
+    action_fingerprints: HashSet<u64>,
+    num_repeated_actions: usize,
 }
 
 impl StrategyPool<'_> {
-    fn new<'a>(world: &World, score_sheet: &'a Vec<ScoreParams>) -> StrategyPool<'a> {
+    fn new<'a>(world: &World, score_sheet: &'a Vec<ScoreParams>, config: &AgentConfig) -> StrategyPool<'a> {
         let mut strategy_id = 0;
-        let rollout = rollouts::rollout(Strategy::new(strategy_id), world, &score_sheet);
+        let rollout = rollouts::rollout(Strategy::new(strategy_id), world, score_sheet);
         strategy_id += 1;
 
+        let mut simulated = LruSet::new(config.rollout_cache_capacity);
+        simulated.insert(rollout.strategy.fingerprint());
+
         StrategyPool {
             strategy_id,
-            entries: (0..score_sheet.len()).map(|i| StrategyPoolEntry::from(&rollout, i)).collect::<Vec<_>>(),
+            entries: (0..score_sheet.len()).map(|i| StrategyPoolEntry::from(&rollout, i, 0)).collect::<Vec<_>>(),
+            action_fingerprints: HashSet::from([rollout.action_fingerprint]),
             best: rollout,
+            best_rollout_index: 0,
+            num_rollouts: 1,
             score_sheet,
+            simulated,
+            num_cache_hits: 0,
+            num_repeated_actions: 0,
         }
     }
 
     fn accept(&mut self, strategy: Strategy, world: &World) {
-        let rollout = rollouts::rollout(strategy, world, &self.score_sheet);
+        // The world and score sheet are fixed for the whole tick, so an identical milestone list
+        // would score identically and could not displace anything
+        let fingerprint = strategy.fingerprint();
+        if self.simulated.touch(&fingerprint) {
+            self.num_cache_hits += 1;
+            return;
+        }
+
+        let rollout = rollouts::rollout(strategy, world, self.score_sheet);
+        let rollout_index = self.num_rollouts;
+        self.num_rollouts += 1;
+        self.simulated.insert(fingerprint);
+
+        // Different milestones can still play out identically, which only the simulation reveals
+        if !self.action_fingerprints.insert(rollout.action_fingerprint) {
+            self.num_repeated_actions += 1;
+        }
 
         // Improve pool
         for i in 0..self.entries.len() {
             let score = rollout.scores[i];
             if score > self.entries[i].score {
-                self.entries[i] = StrategyPoolEntry::from(&rollout, i);
+                self.entries[i] = StrategyPoolEntry::from(&rollout, i, rollout_index);
             }
         }
 
         // Improve best
         if rollout.scores[0] > self.best.scores[0] {
             self.best = rollout;
+            self.best_rollout_index = rollout_index;
         }
     }
 
+    fn best_slots(&self) -> Vec<usize> {
+        // Score sheet slots currently holding the official best strategy
+        (0..self.entries.len()).filter(|i| self.entries[*i].rollout_index == self.best_rollout_index).collect::<Vec<usize>>()
+    }
+
     fn import(&mut self, strategies: Vec<Strategy>, world: &World) {
         for strategy in strategies {
             let candidate = strategy.seed(self.strategy_id);
@@ -65,7 +102,7 @@ impl StrategyPool<'_> {
         self.entries.iter().map(|entry| entry.strategy.clone()).collect::<Vec<Strategy>>()
     }
 
-    fn gen(&self, rng: &mut ThreadRng) -> &Strategy {
+    fn gen(&self, rng: &mut StdRng) -> &Strategy {
         &self.entries[rng.gen_range(0..self.entries.len())].strategy
     }
 }
@@ -75,67 +112,97 @@ struct StrategyPoolEntry {
     score: f32,
     actual: f32,
     ending: WorldState,
+    rollout_index: usize,
 }
 
 impl StrategyPoolEntry {
-    fn new() -> StrategyPoolEntry {
-        StrategyPoolEntry {
-            strategy: Strategy::new(-1),
-            score: f32::NEG_INFINITY,
-            actual: f32::NEG_INFINITY,
-            ending: WorldState::new(),
-        }
-    }
-
-    fn from(rollout: &Rollout, score_sheet_index: usize) -> StrategyPoolEntry {
+    fn from(rollout: &Rollout, score_sheet_index: usize, rollout_index: usize) -> StrategyPoolEntry {
         StrategyPoolEntry {
-            strategy: rollout.strategy.clone(),
+            strategy: rollout.simplified(), // Keeps carried-over strategies short and mutations on meaningful milestones
             score: rollout.scores[score_sheet_index],
             actual: rollout.scores[0],
             ending: rollout.ending.clone(),
+            rollout_index,
         }
     }
 }
 
-pub fn choose(world: &World, score_sheet: &Vec<ScoreParams>, previous_strategies: Vec<Strategy>, rng: &mut ThreadRng) -> Vec<Strategy> {
+pub fn choose(world: &World, score_sheet: &mut ScoreSheet, previous_strategies: Vec<Strategy>, config: &AgentConfig, rng: &mut StdRng) -> Vec<Strategy> {
+    let start = Instant::now(); // Importing previous strategies also counts against the budget
     let mut strategy_id = 0;
 
-    let mut pool = StrategyPool::new(world, score_sheet);
+    let lost = bounds::is_lost(world);
+    let doomed_params: Vec<ScoreParams>;
+    let params = if lost {
+        eprintln!("Tick {}: guaranteed loss detected, maximising score banked before the last human dies", world.tick);
+        doomed_params = (0..score_sheet.params.len()).map(|i| if i == 0 { ScoreParams::doomed(config) } else { score_sheet.params[i].clone() }).collect();
+        &doomed_params
+    } else {
+        &score_sheet.params
+    };
+
+    let mut pool = StrategyPool::new(world, params, config);
+    if config.use_opening_book {
+        pool.import(book::lookup(world), world);
+    }
     pool.import(previous_strategies, world);
 
     let initial_scores = pool.entries.iter().map(|entry| entry.score).collect::<Vec<_>>();
 
-    let start = Instant::now();
-    while start.elapsed().as_millis() < MAX_STRATEGY_GENERATION_MILLISECONDS {
+    let mut mutation_stats = MutationStats::new();
+    while within_budget(strategy_id, &start, config) {
         strategy_id += 1;
 
         let initial_strategy = pool.gen(rng);
-        let strategy = generate_strategy(strategy_id, &initial_strategy, world, rng);
+        let strategy = generate_strategy(strategy_id, initial_strategy, world, config, rng, &mut mutation_stats);
         pool.accept(strategy, world);
     }
 
     eprintln!("Chosen generation {} after {} total generations", pool.best.strategy.id, strategy_id);
+    eprintln!("No-op mutations: {}", mutation_stats);
+    eprintln!("Rollout cache: {} duplicate strategies skipped, {} of {} rollouts repeated an earlier action sequence", pool.num_cache_hits, pool.num_repeated_actions, pool.num_rollouts);
     eprintln!("Chosen strategy: {}", &pool.best.strategy);
 
+    let upper_bound = bounds::upper_bound_score(world);
+    let predicted = evaluation::official_score(&pool.best.events);
+    eprintln!("Predicted score {} of upper bound {} (gap {:.1}%)", predicted, upper_bound, bounds::optimality_gap(predicted, upper_bound));
+
     eprintln!("Optimized score (after {} generations):", strategy_id);
     for (i, entry) in pool.entries.iter().enumerate() {
-        eprintln!(" #[{}]: {} -> {} ({}) (h={}, z={})", entry.strategy.id, initial_scores[i], entry.score, entry.actual, entry.ending.num_humans, entry.ending.num_zombies);
+        eprintln!(" #[{}]: {} -> {} ({}) (h={}, z={}, best={})", entry.strategy.id, initial_scores[i], entry.score, entry.actual, entry.ending.num_humans, entry.ending.num_zombies, score_sheet.hits[i]);
     }
 
     eprintln!("Tick {}: chosen strategy rolled out to tick {}", world.tick, pool.best.ending.tick);
     for event in pool.best.events.iter() {
         eprintln!(" {}", event);
     }
-    
-    pool.export()
+
+    let best_slots = pool.best_slots();
+    let strategies = pool.export();
+
+    let num_reseeded = score_sheet.record_best(&best_slots, config, rng);
+    if num_reseeded > 0 {
+        eprintln!("Reseeded {} score sheet entries", num_reseeded);
+    }
+
+    strategies
+}
+
+fn within_budget(num_generations: i32, start: &Instant, config: &AgentConfig) -> bool {
+    // A fixed number of rollouts makes offline runs independent of machine speed
+    if config.max_rollouts_per_tick > 0 {
+        (num_generations as usize) < config.max_rollouts_per_tick
+    } else {
+        start.elapsed().as_millis() < config.max_strategy_generation_milliseconds
+    }
 }
 
-fn generate_strategy(id: i32, incumbent: &Strategy, world: &World, rng: &mut rand::prelude::ThreadRng) -> Strategy {
+fn generate_strategy(id: i32, incumbent: &Strategy, world: &World, config: &AgentConfig, rng: &mut StdRng, mutation_stats: &mut MutationStats) -> Strategy {
     let mut strategy: Option<Strategy> = None;
 
-    if rng.gen::<f32>() < MUTATE_PROPORTION {
+    if rng.gen::<f32>() < config.mutate_proportion {
         let mut candidate = incumbent.seed(id);
-        let mutated = mutations::mutate_strategy(&mut candidate, world, rng);
+        let mutated = mutations::mutate_strategy(&mut candidate, world, config, rng, mutation_stats);
 
         if mutated {
             strategy = Some(candidate);
@@ -143,15 +210,15 @@ fn generate_strategy(id: i32, incumbent: &Strategy, world: &World, rng: &mut ran
     }
 
     if strategy.is_none() {
-        strategy = Some(generate_strategy_from_scratch(id, world, rng));
+        strategy = Some(generate_strategy_from_scratch(id, world, config, rng));
     }
     strategy.unwrap()
 }
 
-fn generate_strategy_from_scratch(id: i32, world: &World, rng: &mut rand::prelude::ThreadRng) -> Strategy {
+fn generate_strategy_from_scratch(id: i32, world: &World, config: &AgentConfig, rng: &mut StdRng) -> Strategy {
     let mut strategy = Strategy::new(id);
 
-    let num_moves = rng.gen_range(0..(MAX_MOVES_FROM_SCRATCH+1)); // MAX_MOVES_FROM_SCRATCH is inclusive
+    let num_moves = rng.gen_range(0..(config.max_moves_from_scratch+1)); // max_moves_from_scratch is inclusive
     for _ in 0..num_moves {
         let target = V2 {
             x: rng.gen_range(0..constants::MAP_WIDTH) as f32,
@@ -161,9 +228,13 @@ fn generate_strategy_from_scratch(id: i32, world: &World, rng: &mut rand::prelud
     }
 
     let mut remaining_zombie_ids = world.zombies.values().map(|zombie| zombie.id).collect::<Vec<i32>>();
-    while remaining_zombie_ids.len() > 0 {
+    while !remaining_zombie_ids.is_empty() {
         let zombie_id = remaining_zombie_ids.remove(rng.gen_range(0..remaining_zombie_ids.len()));
-        strategy.milestones.push(Milestone::KillZombie { zombie_id });
+        if rng.gen::<f32>() < config.intercept_from_scratch_proportion {
+            strategy.milestones.push(Milestone::InterceptZombie { zombie_id });
+        } else {
+            strategy.milestones.push(Milestone::KillZombie { zombie_id });
+        }
     }
 
     strategy
@@ -0,0 +1,123 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+const SOURCE_DIR: &str = "src";
+const LIBRARY: &str = "lib.rs";
+const ENTRY: &str = "main.rs";
+const CRATE_NAME: &str = "codevszombies";
+const BUNDLE_DIR: &str = "target/bundle";
+const STRIPPED_ATTRIBUTES: &[&str] = &["#[cfg(test)]", "#[cfg(feature = \"local\")]"];
+
+/**
+ * Concatenates the crate into a single file for submission to CodinGame.
+ * The library becomes an inline module named after the crate, so the binary's imports resolve unchanged.
+ * Usage: cargo run --bin bundle [output] (defaults to target/bundle/src/main.rs)
+ **/
+fn main() {
+    let output = env::args().nth(1).unwrap_or(format!("{}/src/main.rs", BUNDLE_DIR));
+
+    let dir = Path::new(SOURCE_DIR);
+    let mut bundle = String::from("#![allow(dead_code, unused_imports)]\n\n");
+    bundle.push_str(&inline_file(&dir.join(ENTRY), dir));
+    bundle.push_str(&format!("\nmod {} {{\n", CRATE_NAME));
+    bundle.push_str(&inline_file(&dir.join(LIBRARY), dir));
+    bundle.push_str("}\n");
+
+    if let Some(parent) = Path::new(&output).parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(&output, &bundle).unwrap_or_else(|error| panic!("Unable to write {}: {}", output, error));
+    eprintln!("Bundled {} lines into {}", bundle.lines().count(), output);
+
+    verify(&bundle);
+}
+
+fn inline_file(path: &Path, dir: &Path) -> String {
+    let source = fs::read_to_string(path).unwrap_or_else(|error| panic!("Unable to read {}: {}", path.display(), error));
+
+    let mut result = String::new();
+    let mut lines = source.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        if STRIPPED_ATTRIBUTES.contains(&trimmed) {
+            skip_item(&mut lines);
+            continue;
+        }
+
+        match module_declaration(trimmed) {
+            Some((visibility, name)) => {
+                let indent = &line[..line.len() - line.trim_start().len()];
+                result.push_str(&format!("{}{}mod {} {{\n", indent, visibility, name));
+                result.push_str(&inline_file(&module_path(dir, name), dir));
+                result.push_str(&format!("{}}}\n", indent));
+            },
+            None => {
+                result.push_str(line);
+                result.push('\n');
+            },
+        }
+    }
+    result
+}
+
+fn skip_item<'a>(lines: &mut impl Iterator<Item=&'a str>) {
+    // Skips the item following a stripped attribute: either a single declaration or a braced block
+    let mut depth = 0;
+    let mut opened = false;
+    for line in lines {
+        if line.trim().starts_with("#[") && !opened { continue; } // Further attributes on the same item
+
+        for c in line.chars() {
+            match c {
+                '{' => { depth += 1; opened = true; },
+                '}' => depth -= 1,
+                _ => {},
+            }
+        }
+
+        if opened && depth <= 0 { return; }
+        if !opened && line.trim_end().ends_with(';') { return; }
+    }
+}
+
+fn module_declaration(line: &str) -> Option<(&str, &str)> {
+    // Matches "mod name;" and "pub mod name;"
+    let (visibility, rest) = match line.strip_prefix("pub ") {
+        Some(rest) => ("pub ", rest),
+        None => ("", line),
+    };
+    let name = rest.strip_prefix("mod ")?.strip_suffix(';')?.trim();
+    Some((visibility, name))
+}
+
+fn module_path(dir: &Path, name: &str) -> std::path::PathBuf {
+    let file = dir.join(format!("{}.rs", name));
+    if file.exists() { file } else { dir.join(name).join("mod.rs") }
+}
+
+fn verify(bundle: &str) {
+    // Build the bundle as a standalone crate with only the submission's dependencies
+    let manifest = fs::read_to_string("Cargo.toml").unwrap();
+    let dependencies = manifest.split("[dependencies]").nth(1).unwrap_or("");
+    let crate_manifest = format!("[package]\nname = \"bundle\"\nversion = \"0.1.0\"\nedition = \"2018\"\n\n[workspace]\n\n[dependencies]{}", dependencies);
+
+    fs::create_dir_all(format!("{}/src", BUNDLE_DIR)).unwrap();
+    fs::write(format!("{}/Cargo.toml", BUNDLE_DIR), crate_manifest).unwrap();
+    if fs::read_to_string(format!("{}/src/main.rs", BUNDLE_DIR)).ok().as_deref() != Some(bundle) {
+        fs::write(format!("{}/src/main.rs", BUNDLE_DIR), bundle).unwrap();
+    }
+
+    let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+        .args(["build", "--quiet", "--offline", "--manifest-path", &format!("{}/Cargo.toml", BUNDLE_DIR)])
+        .status()
+        .unwrap();
+    if status.success() {
+        eprintln!("Bundle compiles");
+    } else {
+        eprintln!("Bundle does not compile");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,40 @@
+pub use super::model::*;
+
+use super::openings::OPENINGS;
+
+pub fn fingerprint(world: &World) -> u64 {
+    // FNV-1a over the integer coordinates, so the hash is stable across compilers and platforms
+    const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let mut values = vec![world.ash.pos.x as i32, world.ash.pos.y as i32];
+
+    let mut humans = world.humans.values().collect::<Vec<&Human>>();
+    humans.sort_by_key(|human| human.id);
+    for human in humans {
+        values.extend([human.id, human.pos.x as i32, human.pos.y as i32]);
+    }
+
+    let mut zombies = world.zombies.values().collect::<Vec<&Zombie>>();
+    zombies.sort_by_key(|zombie| zombie.id);
+    for zombie in zombies {
+        values.extend([zombie.id, zombie.pos.x as i32, zombie.pos.y as i32]);
+    }
+
+    let mut hash = FNV_OFFSET;
+    for value in values {
+        for byte in value.to_le_bytes().iter() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
+
+pub fn lookup(world: &World) -> Vec<Strategy> {
+    let key = fingerprint(world);
+    OPENINGS.iter()
+        .filter(|(fingerprint, _)| *fingerprint == key)
+        .filter_map(|(_, text)| text.parse::<Strategy>().ok())
+        .collect::<Vec<Strategy>>()
+}
@@ -0,0 +1,62 @@
+pub use super::model::*;
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs;
+use rand::rngs::StdRng;
+use super::agent;
+use super::book::fingerprint;
+use super::config::AgentConfig;
+use super::evaluation::ScoreSheet;
+use super::referee;
+
+const BOOK_SEARCH_MILLISECONDS: u128 = 5000;
+const BOOK_SEARCH_RESTARTS: i32 = 8;
+
+pub fn run(output: &str, paths: &[String], config: &AgentConfig, rng: &mut StdRng) {
+    // Writes the contents of openings.rs once every search has finished, so the current book stays intact until then
+    let mut config = config.clone();
+    config.max_strategy_generation_milliseconds = BOOK_SEARCH_MILLISECONDS;
+    config.max_rollouts_per_tick = 0;
+    config.use_opening_book = false; // Otherwise each book is seeded by the previous one
+
+    let mut openings = HashMap::<u64, (String, f32, Strategy)>::new();
+
+    for path in paths.iter() {
+        let initial = referee::load_scenario(path);
+
+        for _ in 0..BOOK_SEARCH_RESTARTS {
+            let mut score_sheet = ScoreSheet::gen(&config, rng);
+            let strategies = agent::choose(&initial, &mut score_sheet, Vec::new(), &config, rng);
+            // Score the text the agent will parse from OPENINGS, not the in-memory strategy
+            let text = strategies[0].seed(0).to_string();
+            let strategy = text.parse::<Strategy>().unwrap_or_else(|error| panic!("Unparseable book strategy {}: {}", text, error));
+            let (result, _) = referee::replay(&initial, &strategy);
+
+            match openings.entry(fingerprint(&initial)) {
+                Entry::Occupied(mut entry) => {
+                    if result.score > entry.get().1 {
+                        entry.insert((path.clone(), result.score, strategy));
+                    }
+                },
+                Entry::Vacant(entry) => {
+                    entry.insert((path.clone(), result.score, strategy));
+                },
+            }
+        }
+    }
+
+    let mut entries = openings.into_iter().collect::<Vec<_>>();
+    entries.sort_by(|a, b| (a.1).0.cmp(&(b.1).0));
+
+    let mut text = String::new();
+    text.push_str("// Generated by `codevszombies book src/openings.rs scenarios/*.txt` - do not edit by hand\n");
+    text.push_str("pub const OPENINGS: &[(u64, &str)] = &[\n");
+    for (fingerprint, (path, score, strategy)) in entries.iter() {
+        text.push_str(&format!("    ({:#018x}, \"{}\"), // {}: {}\n", fingerprint, strategy.to_string().trim(), path, score));
+    }
+    text.push_str("];\n");
+
+    fs::write(output, text).unwrap_or_else(|error| panic!("Unable to write {}: {}", output, error));
+    eprintln!("Wrote {} openings to {}", entries.len(), output);
+}
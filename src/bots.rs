@@ -0,0 +1,128 @@
+pub use super::model::*;
+
+use std::mem;
+use rand::rngs::StdRng;
+use super::agent;
+use super::collections;
+use super::config::AgentConfig;
+use super::evaluation::ScoreSheet;
+use super::rollouts;
+use super::simulator;
+
+pub const BOT_NAMES: &[&str] = &["pool", "nearest-zombie", "guard"];
+
+pub trait Bot {
+    fn act(&mut self, world: &World) -> Action;
+
+    fn start(&mut self, _world: &World) {}
+    fn end(&mut self, _world: &World, _score: f32) {}
+
+    // Milliseconds the bot may spend choosing its next action
+    fn set_budget(&mut self, _milliseconds: u128) {}
+
+    // Called after each action, for per-tick diagnostics
+    fn log(&self, _world: &World) {}
+}
+
+pub fn create(name: &str, config: &AgentConfig, rng: StdRng) -> Result<Box<dyn Bot>, String> {
+    match name {
+        "pool" => Ok(Box::new(PoolBot::new(config.clone(), rng))),
+        "nearest-zombie" => Ok(Box::new(NearestZombieBot)),
+        "guard" => Ok(Box::new(GuardBot)),
+        _ => Err(format!("Unknown bot {}, expected one of {}", name, BOT_NAMES.join(", "))),
+    }
+}
+
+pub struct PoolBot {
+    config: AgentConfig,
+    rng: StdRng,
+    score_sheet: ScoreSheet,
+    previous_strategies: Vec<Strategy>,
+}
+
+impl PoolBot {
+    pub fn new(config: AgentConfig, mut rng: StdRng) -> PoolBot {
+        let score_sheet = ScoreSheet::gen(&config, &mut rng);
+        PoolBot {
+            config,
+            rng,
+            score_sheet,
+            previous_strategies: Vec::new(),
+        }
+    }
+}
+
+impl Bot for PoolBot {
+    fn act(&mut self, world: &World) -> Action {
+        let previous_strategies = mem::take(&mut self.previous_strategies);
+        let strategies = agent::choose(world, &mut self.score_sheet, previous_strategies, &self.config, &mut self.rng);
+        let action = rollouts::strategy_to_action(&strategies[0], world);
+        self.previous_strategies = strategies.iter().map(|strategy| rollouts::advance(strategy, world)).collect();
+        action
+    }
+
+    fn set_budget(&mut self, milliseconds: u128) {
+        self.config.max_strategy_generation_milliseconds = milliseconds;
+    }
+
+    fn log(&self, _world: &World) {
+        if let Some(strategy) = self.previous_strategies.first() {
+            eprintln!("Carrying over: {}", strategy);
+        }
+    }
+}
+
+// Baseline: chase whichever zombie is closest to Ash
+pub struct NearestZombieBot;
+
+impl Bot for NearestZombieBot {
+    fn act(&mut self, world: &World) -> Action {
+        let zombies = world.zombies.values().collect::<Vec<&Zombie>>();
+        match collections::min_by_fkey(&zombies, |zombie| zombie.next.distance_to(world.ash.pos)) {
+            Some(zombie) => Action { target: zombie.next },
+            None => Action { target: world.ash.pos },
+        }
+    }
+}
+
+// Baseline: stand by the human whose hunter is closest to reaching them
+pub struct GuardBot;
+
+impl Bot for GuardBot {
+    fn act(&mut self, world: &World) -> Action {
+        let humans = world.humans.values().collect::<Vec<&Human>>();
+        let most_threatened = collections::min_by_fkey(&humans, |human| {
+            world.zombies.values()
+                .filter(|zombie| simulator::find_zombie_target(zombie.pos, &world.ash, &world.humans).distance_to(human.pos) <= 0.0)
+                .map(|zombie| zombie.pos.distance_to(human.pos))
+                .fold(f32::INFINITY, f32::min)
+        });
+        match most_threatened {
+            Some(human) => Action { target: human.pos },
+            None => Action { target: world.ash.pos },
+        }
+    }
+}
+
+// Follows a fixed strategy without searching, exactly as it would be carried over between ticks
+pub struct ScriptedBot {
+    strategy: Strategy,
+}
+
+impl ScriptedBot {
+    pub fn new(strategy: Strategy) -> ScriptedBot {
+        ScriptedBot { strategy }
+    }
+}
+
+impl Bot for ScriptedBot {
+    fn act(&mut self, world: &World) -> Action {
+        let action = rollouts::strategy_to_action(&self.strategy, world);
+        self.strategy = rollouts::advance(&self.strategy, world);
+        action
+    }
+
+    fn log(&self, _world: &World) {
+        eprintln!("Remaining script: {}", self.strategy);
+    }
+}
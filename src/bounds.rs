@@ -0,0 +1,68 @@
+pub use super::model::*;
+
+use super::simulator;
+use super::simulator::FibonacciSequence;
+
+const FLOOR_DELAY_TICKS: usize = 2;
+
+pub fn upper_bound_score(world: &World) -> f32 {
+    // Optimistic: every zombie dies in a single combo before any human is eaten.
+    // Kills are priced at the current number of humans, which can only go down during the game
+    let num_humans = world.humans.len() as i32;
+    if num_humans == 0 { return 0.0; }
+
+    let score = simulator::calculate_zombie_kill_score(num_humans);
+    let mut multiplier_sequence = FibonacciSequence::new();
+
+    let mut total = 0.0;
+    for _ in 0..world.zombies.len() {
+        total += score * multiplier_sequence.next() as f32;
+    }
+    total
+}
+
+pub fn optimality_gap(score: f32, upper_bound: f32) -> f32 {
+    // Percentage of the upper bound that was not achieved
+    if upper_bound > 0.0 {
+        100.0 * (upper_bound - score) / upper_bound
+    } else {
+        0.0
+    }
+}
+
+pub fn savable_humans(world: &World) -> Vec<&Human> {
+    world.humans.values().filter(|human| is_savable(human, world)).collect::<Vec<_>>()
+}
+
+pub fn is_lost(world: &World) -> bool {
+    // Every remaining human will be eaten whatever Ash does
+    !world.humans.is_empty() && !world.zombies.is_empty() && savable_humans(world).is_empty()
+}
+
+fn is_savable(human: &Human, world: &World) -> bool {
+    // Only zombies currently heading for this human are a threat - the others may still be lured away
+    world.zombies.values().all(|zombie| {
+        let target = simulator::find_zombie_target(zombie.pos, &world.ash, &world.humans);
+        if target.distance_to(human.pos) > 0.0 { return true; }
+
+        // Ash must either shoot the zombie before it arrives (zombies are shot before humans are eaten),
+        // or get closer to the zombie than the human is so the zombie chases Ash instead
+        let mut path = simulator::project_zombie(zombie, world, max_arrival_ticks(zombie, human));
+        let zombie_ticks = match path.iter().position(|pos| pos.distance_to(human.pos) <= constants::MAX_ZOMBIE_KILL_RANGE) {
+            Some(arrival) => arrival + 1,
+            None => return true, // Not close enough to be a certain threat
+        };
+        path.truncate(zombie_ticks);
+
+        path.iter().enumerate().any(|(i, pos)| {
+            let ash_step = ((i + 1) as f32) * constants::MAX_ASH_STEP;
+            let lure_range = if i + 1 < zombie_ticks { pos.distance_to(human.pos) } else { 0.0 };
+            world.ash.pos.distance_to(*pos) <= ash_step + constants::MAX_ASH_KILL_RANGE.max(lure_range)
+        })
+    })
+}
+
+fn max_arrival_ticks(zombie: &Zombie, human: &Human) -> usize {
+    // Positions are floored after every step, which can cost a zombie an extra tick over the straight-line estimate
+    (zombie.pos.distance_to(human.pos) / constants::MAX_ZOMBIE_STEP).ceil() as usize + FLOOR_DELAY_TICKS
+}
@@ -1,14 +1,60 @@
-pub fn min_by_fkey<T, F>(vec: &Vec<T>, selector: F) -> Option<&T>
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+pub fn min_by_fkey<T, F>(vec: &[T], selector: F) -> Option<&T>
 where F: Fn(&T) -> f32 {
 
     let mut result: Option<&T> = None;
     let mut result_value = f32::INFINITY;
     for item in vec.iter() {
-        let value = selector(&item);
+        let value = selector(item);
         if value < result_value {
-            result = Some(&item);
+            result = Some(item);
             result_value = value;
         }
     }
     result
-}
\ No newline at end of file
+}
+
+pub struct LruSet<K> {
+    capacity: usize,
+    clock: u64,
+    last_used: HashMap<K, u64>,
+    recency: BTreeMap<u64, K>, // last use -> key, oldest first
+}
+
+impl<K: Hash + Eq + Clone> LruSet<K> {
+    pub fn new(capacity: usize) -> LruSet<K> {
+        LruSet { capacity, clock: 0, last_used: HashMap::new(), recency: BTreeMap::new() }
+    }
+
+    pub fn touch(&mut self, key: &K) -> bool {
+        // Whether the key is present, marking it as most recently used if so
+        let last_used = match self.last_used.get_mut(key) {
+            Some(last_used) => last_used,
+            None => return false,
+        };
+        self.clock += 1;
+        self.recency.remove(last_used);
+        self.recency.insert(self.clock, key.clone());
+        *last_used = self.clock;
+        true
+    }
+
+    pub fn insert(&mut self, key: K) {
+        if self.capacity == 0 { return; }
+
+        self.clock += 1;
+        if let Some(last_used) = self.last_used.insert(key.clone(), self.clock) {
+            self.recency.remove(&last_used);
+        }
+        self.recency.insert(self.clock, key);
+
+        while self.last_used.len() > self.capacity {
+            match self.recency.pop_first() {
+                Some((_, oldest)) => { self.last_used.remove(&oldest); },
+                None => break,
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt;
+use std::fs;
+use super::evaluation::SheetDistribution;
+
+macro_rules! agent_config {
+    ($($name:ident: $t:ty = $default:expr,)*) => {
+        #[derive(Clone)]
+        pub struct AgentConfig {
+            $(pub $name: $t,)*
+        }
+
+        impl AgentConfig {
+            pub fn new() -> AgentConfig {
+                AgentConfig {
+                    $($name: $default,)*
+                }
+            }
+
+            pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+                match key.trim() {
+                    $(stringify!($name) => {
+                        self.$name = value.trim().parse::<$t>().map_err(|_| format!("Invalid value for {}: {}", key, value))?;
+                    },)*
+                    _ => return Err(format!("Unknown config key: {}", key)),
+                }
+                Ok(())
+            }
+
+            pub fn get(&self, key: &str) -> Option<String> {
+                match key.trim() {
+                    $(stringify!($name) => Some(self.$name.to_string()),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl Default for AgentConfig {
+            fn default() -> AgentConfig {
+                AgentConfig::new()
+            }
+        }
+
+        impl fmt::Display for AgentConfig {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                $(writeln!(f, "{} = {}", stringify!($name), self.$name)?;)*
+                Ok(())
+            }
+        }
+    }
+}
+
+agent_config! {
+    // Search
+    max_strategy_generation_milliseconds: u128 = 90,
+    max_rollouts_per_tick: usize = 0, // 0 means only the time limit applies
+    mutate_proportion: f32 = 0.9,
+    max_moves_from_scratch: i32 = 1,
+    intercept_from_scratch_proportion: f32 = 0.5,
+    latency_target_milliseconds: u128 = 95, // total tick latency the search budget adapts to
+    latency_warning_milliseconds: u128 = 100,
+    use_opening_book: bool = true, // seed the pool from the opening book when the initial world is recognised
+    rollout_cache_capacity: usize = 4096, // strategies remembered per tick so duplicates are not re-simulated, 0 disables
+
+    // Mutations
+    replace_move_proportion: f32 = 0.5,
+    bump_move_proportion: f32 = 0.25,
+    anchored_move_proportion: f32 = 0.1,
+    bump_offset_proportion: f32 = 0.1,
+    attack_zombie_proportion: f32 = 0.1,
+    intercept_zombie_proportion: f32 = 0.1,
+    switch_attack_proportion: f32 = 0.1,
+    combo_proportion: f32 = 0.1,
+    protect_human_proportion: f32 = 0.1,
+    tune_guard_proportion: f32 = 0.1,
+    drop_proportion: f32 = 0.05,
+    wait_proportion: f32 = 0.05,
+    adjust_wait_proportion: f32 = 0.1,
+    bubble_proportion: f32 = 0.1,
+    swap_proportion: f32 = 0.05,
+    displace_proportion: f32 = 0.5,
+
+    // Evaluation
+    score_sheet_size: usize = 5,
+    score_sheet_distribution: SheetDistribution = SheetDistribution::Uniform,
+    score_sheet_spread: f32 = 0.5,
+    max_urgency_weight: f32 = 0.0,
+    max_terminal_weight: f32 = 0.0,
+    reseed_interval: i32 = 10, // ticks between replacing score sheet entries that never produced the best strategy, 0 disables
+    reseed_mutate_proportion: f32 = 0.5,
+    won_points: f32 = 0.0,
+    loss_points: f32 = -10000.0,
+    points_per_zombie: f32 = -1000.0,
+    bonus_points_per_human: f32 = -10000.0,
+    points_per_tick: f32 = -0.01,
+    points_per_milestone: f32 = -0.001,
+}
+
+impl AgentConfig {
+    // One "key = value" per line, blank lines and # comments are ignored
+    pub fn parse(text: &str) -> Result<AgentConfig, String> {
+        let mut config = AgentConfig::new();
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() { continue; }
+
+            let (key, value) = line.split_once('=').ok_or(format!("Expected key = value: {}", line))?;
+            config.set(key, value)?;
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: &str) -> Result<AgentConfig, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("Unable to read {}: {}", path, error))?;
+        AgentConfig::parse(&text)
+    }
+
+    pub fn apply(&mut self, assignment: &str) -> Result<(), String> {
+        let (key, value) = assignment.split_once('=').ok_or(format!("Expected key=value: {}", assignment))?;
+        self.set(key, value)
+    }
+}
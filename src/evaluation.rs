@@ -1,52 +1,223 @@
 pub use super::model::*;
 
-use rand;
 use rand::Rng;
-use rand::prelude::ThreadRng;
-
-const SCORE_SHEET_SIZE: i32 = 5;
-
-const WON_POINTS: f32 = 0.0;
-const LOSS_POINTS: f32 = -10000.0;
-const POINTS_PER_ZOMBIE: f32 = -1000.0;
-const BONUS_POINTS_PER_HUMAN: f32 = -10000.0;
-const POINTS_PER_TICK: f32 = -0.01;
-const POINTS_PER_MILESTONE: f32 = -0.001;
+use rand::rngs::StdRng;
+use std::fmt;
+use std::str::FromStr;
+use super::bounds;
+use super::config::AgentConfig;
 
+#[derive(Clone)]
 pub struct ScoreParams {
     save_humans_weight: f32,
     kill_zombies_score_weight: f32,
     kill_zombies_multiplier_weight: f32,
+    urgency_weight: f32,
+    terminal_weight: f32,
+    penalties: Penalties,
+}
+
+#[derive(Clone)]
+struct Penalties {
+    won_points: f32,
+    loss_points: f32,
+    points_per_zombie: f32,
+    bonus_points_per_human: f32,
+    points_per_tick: f32,
+    points_per_milestone: f32,
+}
+
+impl Penalties {
+    fn from(config: &AgentConfig) -> Penalties {
+        Penalties {
+            won_points: config.won_points,
+            loss_points: config.loss_points,
+            points_per_zombie: config.points_per_zombie,
+            bonus_points_per_human: config.bonus_points_per_human,
+            points_per_tick: config.points_per_tick,
+            points_per_milestone: config.points_per_milestone,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum SheetDistribution {
+    Uniform, // Weights drawn independently from [0, 1)
+    Perturbed, // Weights drawn within score_sheet_spread of the official objective
 }
 
 impl ScoreParams {
-    pub fn official() -> ScoreParams {
-        ScoreParams {
-            save_humans_weight: 0.0,
-            kill_zombies_score_weight: 1.0,
-            kill_zombies_multiplier_weight: 1.0,
+    pub fn builder(config: &AgentConfig) -> ScoreParamsBuilder {
+        ScoreParamsBuilder {
+            params: ScoreParams {
+                save_humans_weight: 0.0,
+                kill_zombies_score_weight: 1.0,
+                kill_zombies_multiplier_weight: 1.0,
+                urgency_weight: 0.0,
+                terminal_weight: 0.0,
+                penalties: Penalties::from(config),
+            },
         }
     }
 
-    pub fn gen(rng: &mut ThreadRng) -> ScoreParams {
-        ScoreParams {
-            kill_zombies_score_weight: rng.gen::<f32>(),
-            kill_zombies_multiplier_weight: 1.0,
-            save_humans_weight: rng.gen::<f32>(),
+    pub fn official(config: &AgentConfig) -> ScoreParams {
+        ScoreParams::builder(config).build()
+    }
+
+    pub fn doomed(config: &AgentConfig) -> ScoreParams {
+        // The game cannot be won, so every rollout loses equally - only the kills before the last human dies differ.
+        // Ties go to the rollout where the last human survives longest, leaving more time to kill
+        ScoreParams::builder(config)
+            .loss_points(0.0)
+            .points_per_zombie(0.0)
+            .points_per_tick(config.points_per_tick.abs())
+            .build()
+    }
+
+    pub fn gen(config: &AgentConfig, rng: &mut StdRng) -> ScoreParams {
+        let builder = ScoreParams::builder(config)
+            .urgency_weight(rng.gen::<f32>() * config.max_urgency_weight)
+            .terminal_weight(rng.gen::<f32>() * config.max_terminal_weight);
+
+        match config.score_sheet_distribution {
+            SheetDistribution::Uniform => builder
+                .kill_zombies_score_weight(rng.gen::<f32>())
+                .save_humans_weight(rng.gen::<f32>())
+                .build(),
+            SheetDistribution::Perturbed => {
+                let spread = config.score_sheet_spread;
+                builder
+                    .kill_zombies_score_weight(1.0 + spread * rng.gen_range(-1.0..1.0f32))
+                    .kill_zombies_multiplier_weight(1.0 + spread * rng.gen_range(-1.0..1.0f32))
+                    .save_humans_weight(spread * rng.gen::<f32>())
+                    .build()
+            },
         }
     }
 
-    pub fn gen_sheet(rng: &mut ThreadRng) -> Vec<ScoreParams> {
-        let mut score_sheet = vec![ScoreParams::official()];
+    pub fn mutate(&self, config: &AgentConfig, rng: &mut StdRng) -> ScoreParams {
+        let spread = config.score_sheet_spread;
+        ScoreParams::builder(config)
+            .save_humans_weight((self.save_humans_weight + spread * rng.gen_range(-0.5..0.5f32)).max(0.0))
+            .kill_zombies_score_weight((self.kill_zombies_score_weight + spread * rng.gen_range(-0.5..0.5f32)).max(0.0))
+            .kill_zombies_multiplier_weight((self.kill_zombies_multiplier_weight + spread * rng.gen_range(-0.5..0.5f32)).max(0.0))
+            .urgency_weight(self.urgency_weight)
+            .terminal_weight(self.terminal_weight)
+            .build()
+    }
+
+    pub fn gen_sheet(config: &AgentConfig, rng: &mut StdRng) -> Vec<ScoreParams> {
+        let mut score_sheet = vec![ScoreParams::official(config)];
 
-        for _ in 0..SCORE_SHEET_SIZE {
-            score_sheet.push(ScoreParams::gen(rng));
+        for _ in 0..config.score_sheet_size {
+            score_sheet.push(ScoreParams::gen(config, rng));
         }
 
         score_sheet
     }
 }
 
+pub struct ScoreSheet {
+    pub params: Vec<ScoreParams>,
+    pub hits: Vec<i32>, // Number of ticks each slot held the official best strategy since the last reseed
+    num_ticks: i32,
+}
+
+impl ScoreSheet {
+    pub fn gen(config: &AgentConfig, rng: &mut StdRng) -> ScoreSheet {
+        let params = ScoreParams::gen_sheet(config, rng);
+        ScoreSheet {
+            hits: vec![0; params.len()],
+            params,
+            num_ticks: 0,
+        }
+    }
+
+    pub fn record_best(&mut self, slots: &[usize], config: &AgentConfig, rng: &mut StdRng) -> usize {
+        for slot in slots.iter() {
+            self.hits[*slot] += 1;
+        }
+
+        self.num_ticks += 1;
+        if config.reseed_interval > 0 && self.num_ticks % config.reseed_interval == 0 {
+            self.reseed(config, rng)
+        } else {
+            0
+        }
+    }
+
+    fn reseed(&mut self, config: &AgentConfig, rng: &mut StdRng) -> usize {
+        // Slot 0 is the official objective and is never replaced
+        let most_successful = (1..self.params.len()).filter(|i| self.hits[*i] > 0).max_by_key(|i| self.hits[*i]);
+
+        let mut num_reseeded = 0;
+        for i in 1..self.params.len() {
+            if self.hits[i] > 0 { continue; }
+
+            self.params[i] = match most_successful {
+                Some(source) if rng.gen::<f32>() < config.reseed_mutate_proportion => self.params[source].mutate(config, rng),
+                _ => ScoreParams::gen(config, rng),
+            };
+            num_reseeded += 1;
+        }
+
+        self.hits = vec![0; self.params.len()];
+        num_reseeded
+    }
+}
+
+pub struct ScoreParamsBuilder {
+    params: ScoreParams,
+}
+
+impl ScoreParamsBuilder {
+    pub fn save_humans_weight(mut self, weight: f32) -> ScoreParamsBuilder {
+        self.params.save_humans_weight = weight;
+        self
+    }
+
+    pub fn kill_zombies_score_weight(mut self, weight: f32) -> ScoreParamsBuilder {
+        self.params.kill_zombies_score_weight = weight;
+        self
+    }
+
+    pub fn kill_zombies_multiplier_weight(mut self, weight: f32) -> ScoreParamsBuilder {
+        self.params.kill_zombies_multiplier_weight = weight;
+        self
+    }
+
+    // Proportion by which the value of an event decays for each tick it lies in the future
+    pub fn urgency_weight(mut self, weight: f32) -> ScoreParamsBuilder {
+        self.params.urgency_weight = weight;
+        self
+    }
+
+    // Weight of the optimistic score still available when a rollout is cut off before the game ends
+    pub fn terminal_weight(mut self, weight: f32) -> ScoreParamsBuilder {
+        self.params.terminal_weight = weight;
+        self
+    }
+
+    pub fn loss_points(mut self, points: f32) -> ScoreParamsBuilder {
+        self.params.penalties.loss_points = points;
+        self
+    }
+
+    pub fn points_per_zombie(mut self, points: f32) -> ScoreParamsBuilder {
+        self.params.penalties.points_per_zombie = points;
+        self
+    }
+
+    pub fn points_per_tick(mut self, points: f32) -> ScoreParamsBuilder {
+        self.params.penalties.points_per_tick = points;
+        self
+    }
+
+    pub fn build(self) -> ScoreParams {
+        self.params
+    }
+}
+
 pub struct ScoreAccumulator<'a> {
     pub initial_tick: i32,
     pub total_score: f32,
@@ -59,28 +230,71 @@ impl ScoreAccumulator<'_> {
     }
 
     pub fn evaluate_strategy(&mut self, strategy: &Strategy) {
-        self.total_score += POINTS_PER_MILESTONE * (strategy.milestones.len() as f32);
+        self.total_score += self.params.penalties.points_per_milestone * (strategy.milestones.len() as f32);
     }
 
-    pub fn accumulate(&mut self, events: &Vec<Event>) {
+    pub fn accumulate(&mut self, events: &[Event]) {
         for event in events.iter() {
             match event {
-                Event::ZombieKilled { score, multiplier, .. } => {
-                    self.total_score += self.params.kill_zombies_score_weight * score * multiplier.powf(self.params.kill_zombies_multiplier_weight);
+                Event::ZombieKilled { tick, score, multiplier, .. } => {
+                    self.total_score += self.urgency(*tick) * self.params.kill_zombies_score_weight * score * multiplier.powf(self.params.kill_zombies_multiplier_weight);
                 },
-                Event::HumanKilled { .. } => {
-                    self.total_score += self.params.save_humans_weight * BONUS_POINTS_PER_HUMAN;
+                Event::HumanKilled { tick, .. } => {
+                    self.total_score += self.urgency(*tick) * self.params.save_humans_weight * self.params.penalties.bonus_points_per_human;
                 },
                 Event::Won{ tick, .. } => {
-                    self.total_score += POINTS_PER_TICK * (*tick as f32);
-                    self.total_score += WON_POINTS;
+                    self.total_score += self.params.penalties.points_per_tick * (*tick as f32);
+                    self.total_score += self.params.penalties.won_points;
                 },
                 Event::Lost{ tick, num_zombies, .. } => {
-                    self.total_score += POINTS_PER_TICK * (*tick as f32);
-                    self.total_score += POINTS_PER_ZOMBIE * (*num_zombies as f32);
-                    self.total_score += LOSS_POINTS;
+                    self.total_score += self.params.penalties.points_per_tick * (*tick as f32);
+                    self.total_score += self.params.penalties.points_per_zombie * (*num_zombies as f32);
+                    self.total_score += self.params.penalties.loss_points;
                 },
             }
         }
     }
+
+    pub fn evaluate_ending(&mut self, world: &World) {
+        // Only called when the rollout ran out of ticks before the game was decided
+        if self.params.terminal_weight != 0.0 {
+            self.total_score += self.urgency(world.tick) * self.params.terminal_weight * bounds::upper_bound_score(world);
+        }
+    }
+
+    fn urgency(&self, tick: i32) -> f32 {
+        (1.0 - self.params.urgency_weight).powi(tick - self.initial_tick)
+    }
+}
+
+pub fn official_score(events: &[Event]) -> f32 {
+    // Losing the game scores nothing, regardless of how many zombies were killed along the way
+    if events.iter().any(|event| matches!(event, Event::Lost{..})) { return 0.0; }
+
+    events.iter().map(|event| match event {
+        Event::ZombieKilled { score, multiplier, .. } => score * multiplier,
+        _ => 0.0,
+    }).sum()
+}
+
+
+impl FromStr for SheetDistribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<SheetDistribution, String> {
+        match s {
+            "uniform" => Ok(SheetDistribution::Uniform),
+            "perturbed" => Ok(SheetDistribution::Perturbed),
+            _ => Err(format!("Unknown score sheet distribution: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for SheetDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            SheetDistribution::Uniform => write!(f, "uniform"),
+            SheetDistribution::Perturbed => write!(f, "perturbed"),
+        }
+    }
 }
\ No newline at end of file
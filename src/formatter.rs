@@ -7,29 +7,57 @@ impl fmt::Display for Action {
     }
 }
 
+// Floats print in their shortest exact form so that strategies copied from the logs replay identically
 impl fmt::Display for Milestone {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self {
             Milestone::KillZombie { zombie_id } => { write!(f, "z{} ", zombie_id) },
-            Milestone::ProtectHuman { human_id } => { write!(f, "h{} ", human_id) },
-            Milestone::MoveTo { target } => { write!(f, "({:.0},{:.0}) ", target.x, target.y) },
+            Milestone::InterceptZombie { zombie_id } => { write!(f, "i{} ", zombie_id) },
+            Milestone::ComboAt { zombie_ids } => {
+                let ids = zombie_ids.iter().map(|zombie_id| zombie_id.to_string()).collect::<Vec<String>>();
+                write!(f, "c{} ", ids.join("+"))
+            },
+            Milestone::ProtectHuman { human_id, until } => { write!(f, "h{}{} ", human_id, until) },
+            Milestone::MoveTo { target } => { write!(f, "({},{}) ", target.x, target.y) },
+            Milestone::MoveNear { anchor, offset } => { write!(f, "@{}({},{}) ", anchor, offset.x, offset.y) },
+            Milestone::Wait { ticks } => { write!(f, "w{} ", ticks) },
+            Milestone::WaitUntilZombiesWithin { count, radius } => { write!(f, "wz{}@{} ", count, radius) },
+        }
+    }
+}
+
+impl fmt::Display for Anchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            Anchor::Human(human_id) => write!(f, "h{}", human_id),
+            Anchor::Zombie(zombie_id) => write!(f, "z{}", zombie_id),
+            Anchor::Centroid(zombie_ids) => {
+                let ids = zombie_ids.iter().map(|zombie_id| zombie_id.to_string()).collect::<Vec<String>>();
+                write!(f, "c{}", ids.join("+"))
+            },
+        }
+    }
+}
+
+impl fmt::Display for GuardCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            GuardCondition::Ticks(ticks) => write!(f, "t{}", ticks),
+            GuardCondition::ZombiesNear(count) => write!(f, "n{}", count),
+            GuardCondition::ThreatCleared => Ok(()),
         }
     }
 }
 
 impl fmt::Display for Strategy {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "[{}] ", self.id).unwrap();
+        write!(f, "[{}] ", self.id)?;
 
         for milestone in self.milestones.iter() {
-            match milestone {
-                Milestone::KillZombie { zombie_id } => { write!(f, "z{} ", zombie_id).unwrap(); },
-                Milestone::ProtectHuman { human_id } => { write!(f, "h{} ", human_id).unwrap(); },
-                Milestone::MoveTo { target } => { write!(f, "({:.0},{:.0}) ", target.x, target.y).unwrap(); },
-            }
+            write!(f, "{}", milestone)?;
         }
 
-        write!(f, "") // TODO: Return the right value without doing this
+        Ok(())
     }
 }
 
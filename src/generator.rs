@@ -0,0 +1,238 @@
+pub use super::model::*;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use super::bounds;
+use super::parser;
+use super::simulator;
+
+const CLUSTER_RADIUS: f32 = 1500.0;
+const MAX_PLACEMENT_ATTEMPTS: i32 = 100;
+const DOOMED_ASH_DISTANCE: f32 = constants::MAX_ASH_STEP + constants::MAX_ASH_KILL_RANGE + 100.0; // Out of reach before the zombie arrives
+
+#[derive(Clone, Copy)]
+pub enum AshPlacement {
+    Random,
+    Centre,
+    Corner,
+    Far, // As far from the humans as possible
+}
+
+#[derive(Clone)]
+pub struct ScenarioParams {
+    pub num_humans: usize,
+    pub num_zombies: usize,
+    pub clustering: f32, // probability of spawning near an existing human/zombie rather than anywhere
+    pub min_zombie_distance: f32, // from every human
+    pub ash_placement: AshPlacement,
+    pub doomed_proportion: f32, // humans given a zombie that will eat them before Ash can possibly intervene
+}
+
+impl ScenarioParams {
+    pub fn new() -> ScenarioParams {
+        ScenarioParams {
+            num_humans: 5,
+            num_zombies: 10,
+            clustering: 0.3,
+            min_zombie_distance: 2000.0,
+            ash_placement: AshPlacement::Random,
+            doomed_proportion: 0.0,
+        }
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key.trim() {
+            "humans" => self.num_humans = parse_value(key, value)?,
+            "zombies" => self.num_zombies = parse_value(key, value)?,
+            "clustering" => self.clustering = parse_value(key, value)?,
+            "zombie_distance" => self.min_zombie_distance = parse_value(key, value)?,
+            "ash" => self.ash_placement = value.trim().parse::<AshPlacement>()?,
+            "doomed" => self.doomed_proportion = parse_value(key, value)?,
+            _ => return Err(format!("Unknown scenario parameter: {}", key)),
+        }
+        Ok(())
+    }
+}
+
+fn parse_value<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.trim().parse::<T>().map_err(|_| format!("Invalid value for {}: {}", key, value))
+}
+
+impl Default for ScenarioParams {
+    fn default() -> ScenarioParams {
+        ScenarioParams::new()
+    }
+}
+
+pub fn generate(params: &ScenarioParams, seed: u64) -> Result<World, String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let num_humans = params.num_humans;
+    if num_humans == 0 { return Err("A scenario needs at least one human".to_string()); }
+
+    let mut human_positions = Vec::<V2>::new();
+    for _ in 0..num_humans {
+        let pos = gen_clustered(&human_positions, params.clustering, &mut rng);
+        human_positions.push(pos);
+    }
+
+    let ash = Ash { pos: gen_ash(params.ash_placement, &human_positions, &mut rng) };
+
+    // Doomed humans get a zombie right next to them, never all of them or the game would be lost from the start
+    let num_doomed = ((params.doomed_proportion * num_humans as f32).round() as usize).min(num_humans - 1).min(params.num_zombies);
+    let mut zombie_positions = Vec::<V2>::new();
+    for human_pos in human_positions.iter() {
+        if zombie_positions.len() >= num_doomed { break; }
+        if ash.pos.distance_to(*human_pos) < DOOMED_ASH_DISTANCE { continue; }
+
+        let pos = (0..MAX_PLACEMENT_ATTEMPTS)
+            .map(|_| clamp(human_pos.add(gen_offset(constants::MAX_ZOMBIE_STEP - 1.0, &mut rng))))
+            .find(|pos| is_nearest_human(*pos, *human_pos, &human_positions));
+        if let Some(pos) = pos {
+            zombie_positions.push(pos);
+        }
+    }
+
+    while zombie_positions.len() < params.num_zombies {
+        let pos = (0..MAX_PLACEMENT_ATTEMPTS)
+            .map(|_| gen_clustered(&zombie_positions, params.clustering, &mut rng))
+            .find(|pos| human_positions.iter().all(|human_pos| human_pos.distance_to(*pos) >= params.min_zombie_distance))
+            .ok_or(format!("Unable to place zombie {} at least {} from every human (seed {})", zombie_positions.len(), params.min_zombie_distance, seed))?;
+        zombie_positions.push(pos);
+    }
+
+    let mut world = World {
+        tick: 0,
+        ash,
+        humans: human_positions.iter().enumerate().map(|(id, pos)| (id as i32, Human { id: id as i32, pos: *pos })).collect::<BTreeMap<_, _>>(),
+        zombies: zombie_positions.iter().enumerate().map(|(id, pos)| (id as i32, Zombie { id: id as i32, pos: *pos, next: *pos })).collect::<BTreeMap<_, _>>(),
+    };
+    simulator::update_zombie_targets(&mut world);
+    Ok(world)
+}
+
+fn gen_clustered(existing: &[V2], clustering: f32, rng: &mut StdRng) -> V2 {
+    if !existing.is_empty() && rng.gen::<f32>() < clustering {
+        let centre = existing[rng.gen_range(0..existing.len())];
+        clamp(centre.add(gen_offset(CLUSTER_RADIUS, rng)))
+    } else {
+        gen_anywhere(rng)
+    }
+}
+
+fn gen_ash(placement: AshPlacement, human_positions: &[V2], rng: &mut StdRng) -> V2 {
+    match placement {
+        AshPlacement::Random => gen_anywhere(rng),
+        AshPlacement::Centre => V2 { x: (constants::MAP_WIDTH / 2) as f32, y: (constants::MAP_HEIGHT / 2) as f32 },
+        AshPlacement::Corner => V2 {
+            x: if rng.gen::<bool>() { 0.0 } else { (constants::MAP_WIDTH - 1) as f32 },
+            y: if rng.gen::<bool>() { 0.0 } else { (constants::MAP_HEIGHT - 1) as f32 },
+        },
+        AshPlacement::Far => {
+            let nearest_human = |pos: &V2| human_positions.iter().map(|human_pos| human_pos.distance_to(*pos)).fold(f32::INFINITY, f32::min);
+            let mut best = gen_anywhere(rng);
+            for _ in 0..MAX_PLACEMENT_ATTEMPTS {
+                let candidate = gen_anywhere(rng);
+                if nearest_human(&candidate) > nearest_human(&best) {
+                    best = candidate;
+                }
+            }
+            best
+        },
+    }
+}
+
+fn gen_anywhere(rng: &mut StdRng) -> V2 {
+    V2 {
+        x: rng.gen_range(0..constants::MAP_WIDTH) as f32,
+        y: rng.gen_range(0..constants::MAP_HEIGHT) as f32,
+    }
+}
+
+fn gen_offset(radius: f32, rng: &mut StdRng) -> V2 {
+    let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    let distance = rng.gen_range(1.0..radius);
+    V2 { x: distance * angle.cos(), y: distance * angle.sin() }
+}
+
+fn clamp(pos: V2) -> V2 {
+    pos.clamp_to_map().floor()
+}
+
+fn is_nearest_human(pos: V2, human_pos: V2, human_positions: &[V2]) -> bool {
+    let distance = pos.distance_to(human_pos);
+    distance > 0.0 && human_positions.iter().all(|other| other.distance_to(human_pos) <= 0.0 || other.distance_to(pos) > distance)
+}
+
+pub fn scenario_text(world: &World) -> String {
+    // Same format as the CodinGame input for the first tick
+    let mut text = format!("{} {}\n", world.ash.pos.x, world.ash.pos.y);
+
+    let mut humans = world.humans.values().collect::<Vec<&Human>>();
+    humans.sort_by_key(|human| human.id);
+    text.push_str(&format!("{}\n", humans.len()));
+    for human in humans {
+        text.push_str(&format!("{} {} {}\n", human.id, human.pos.x, human.pos.y));
+    }
+
+    let mut zombies = world.zombies.values().collect::<Vec<&Zombie>>();
+    zombies.sort_by_key(|zombie| zombie.id);
+    text.push_str(&format!("{}\n", zombies.len()));
+    for zombie in zombies {
+        text.push_str(&format!("{} {} {} {} {}\n", zombie.id, zombie.pos.x, zombie.pos.y, zombie.next.x, zombie.next.y));
+    }
+    text
+}
+
+pub fn run(count: u64, dir: &str, assignments: &[String]) {
+    let mut params = ScenarioParams::new();
+    for assignment in assignments.iter() {
+        let (key, value) = assignment.split_once('=').unwrap_or_else(|| panic!("Expected key=value: {}", assignment));
+        params.set(key, value).unwrap_or_else(|error| panic!("{}", error));
+    }
+
+    fs::create_dir_all(dir).unwrap_or_else(|error| panic!("Unable to create {}: {}", dir, error));
+    for seed in 0..count {
+        let world = generate(&params, seed).unwrap_or_else(|error| panic!("{}", error));
+        let text = scenario_text(&world);
+
+        // The written file must read back as the same scenario
+        let parsed = parser::read_world_from(&mut text.as_bytes(), 0).unwrap_or_else(|error| panic!("Generated scenario {} is invalid: {}", seed, error));
+        assert!(scenario_text(&parsed) == text, "Generated scenario {} does not round trip", seed);
+
+        let path = format!("{}/generated-{:03}.txt", dir, seed);
+        fs::write(&path, &text).unwrap_or_else(|error| panic!("Unable to write {}: {}", path, error));
+        println!(
+            "{}: h={}, z={}, savable={}, upper bound {}",
+            path, world.humans.len(), world.zombies.len(), bounds::savable_humans(&world).len(), bounds::upper_bound_score(&world));
+    }
+}
+
+impl FromStr for AshPlacement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<AshPlacement, String> {
+        match s {
+            "random" => Ok(AshPlacement::Random),
+            "centre" => Ok(AshPlacement::Centre),
+            "corner" => Ok(AshPlacement::Corner),
+            "far" => Ok(AshPlacement::Far),
+            _ => Err(format!("Unknown ash placement: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for AshPlacement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AshPlacement::Random => write!(f, "random"),
+            AshPlacement::Centre => write!(f, "centre"),
+            AshPlacement::Corner => write!(f, "corner"),
+            AshPlacement::Far => write!(f, "far"),
+        }
+    }
+}
@@ -1,16 +1,31 @@
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub struct V2 {
     pub x: f32,
     pub y: f32,
 }
 
+impl Hash for V2 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_f32(self.x, state);
+        hash_f32(self.y, state);
+    }
+}
+
+pub fn hash_f32<H: Hasher>(value: f32, state: &mut H) {
+    // -0.0 == 0.0, so both must hash the same
+    let value = if value == 0.0 { 0.0 } else { value };
+    value.to_bits().hash(state);
+}
+
 impl V2 {
     pub fn zero() -> V2 {
         V2 { x: 0.0, y: 0.0 }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn add(self, other: V2) -> V2 {
         V2 {
             x: self.x + other.x,
@@ -18,6 +33,7 @@ impl V2 {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn sub(self, other: V2) -> V2 {
         V2 {
             x: self.x - other.x,
@@ -25,6 +41,7 @@ impl V2 {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn mul(self, multiplier: f32) -> V2 {
         V2 {
             x: self.x * multiplier,
@@ -32,6 +49,7 @@ impl V2 {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn div(self, divisor: f32) -> V2 {
         V2 {
             x: self.x / divisor,
@@ -97,11 +115,11 @@ impl V2 {
         let diff = V2::diff(target, self);
         let distance = diff.length();
         if distance < max_step {
-            return target;
+            target
         } else if distance > 0.0 {
-            return self.add(diff.mul(max_step / distance));
+            self.add(diff.mul(max_step / distance))
         } else {
-            return self;
+            self
         }
     }
 }
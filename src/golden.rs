@@ -0,0 +1,44 @@
+use std::fs;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use super::bots::PoolBot;
+use super::config::AgentConfig;
+use super::referee;
+
+pub const GOLDEN_PATH: &str = "tests/golden.txt";
+const GOLDEN_SEED: u64 = 0;
+const GOLDEN_ROLLOUTS_PER_TICK: usize = 200;
+
+pub fn score(path: &str) -> f32 {
+    // A fixed seed and rollout budget make the score independent of machine speed.
+    // The scenarios are in the opening book, which would otherwise replay the same score whatever the search does
+    let mut config = AgentConfig::new();
+    config.max_rollouts_per_tick = GOLDEN_ROLLOUTS_PER_TICK;
+    config.use_opening_book = false;
+
+    let initial = referee::load_scenario(path);
+    let mut bot = PoolBot::new(config, StdRng::seed_from_u64(GOLDEN_SEED));
+    referee::play(&initial, &mut bot).0.score
+}
+
+// One "scenario score" per line
+pub fn load(path: &str) -> Vec<(String, f32)> {
+    let text = fs::read_to_string(path).unwrap_or_else(|error| panic!("Unable to read {}: {}", path, error));
+    text.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (scenario, score) = line.trim().rsplit_once(' ').unwrap_or_else(|| panic!("Expected scenario and score: {}", line));
+            (scenario.to_string(), score.parse::<f32>().unwrap_or_else(|_| panic!("Invalid golden score: {}", line)))
+        })
+        .collect::<Vec<_>>()
+}
+
+pub fn bless(paths: &[String]) {
+    let mut text = String::new();
+    for path in paths.iter() {
+        let score = score(path);
+        println!("{}: {}", path, score);
+        text.push_str(&format!("{} {}\n", path, score));
+    }
+    fs::write(GOLDEN_PATH, text).unwrap_or_else(|error| panic!("Unable to write {}: {}", GOLDEN_PATH, error));
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+use super::config::AgentConfig;
+
+const BUCKET_MILLISECONDS: u128 = 10;
+const NUM_BUCKETS: usize = 16; // Last bucket collects everything slower
+const MIN_BUDGET_MILLISECONDS: u128 = 10;
+
+pub struct LatencyMonitor {
+    histogram: [usize; NUM_BUCKETS],
+    budget: u128,
+    max_budget: u128,
+    target: u128,
+    warning: u128,
+    max_latency: u128,
+}
+
+impl LatencyMonitor {
+    pub fn new(config: &AgentConfig) -> LatencyMonitor {
+        LatencyMonitor {
+            histogram: [0; NUM_BUCKETS],
+            budget: config.max_strategy_generation_milliseconds,
+            max_budget: config.max_strategy_generation_milliseconds,
+            target: config.latency_target_milliseconds,
+            warning: config.latency_warning_milliseconds,
+            max_latency: 0,
+        }
+    }
+
+    pub fn budget(&self) -> u128 {
+        self.budget
+    }
+
+    pub fn record(&mut self, tick: i32, latency: Duration) {
+        // Latency covers everything from the end of the input read to the output flush, not just the search
+        let latency = latency.as_millis();
+        self.histogram[((latency / BUCKET_MILLISECONDS) as usize).min(NUM_BUCKETS - 1)] += 1;
+        self.max_latency = self.max_latency.max(latency);
+
+        if latency > self.warning {
+            eprintln!("WARNING: tick {} took {} ms (warning threshold {} ms)", tick, latency, self.warning);
+        }
+
+        // Shrink quickly when over target, recover slowly once there is headroom again
+        if latency > self.target {
+            self.budget = self.budget.saturating_sub(latency - self.target).max(MIN_BUDGET_MILLISECONDS);
+        } else if self.budget < self.max_budget {
+            self.budget += 1;
+        }
+    }
+
+    pub fn log(&self, tick: i32, latency: Duration) {
+        let buckets = (0..NUM_BUCKETS)
+            .filter(|i| self.histogram[*i] > 0)
+            .map(|i| format!("{}:{}", i as u128 * BUCKET_MILLISECONDS, self.histogram[i]))
+            .collect::<Vec<String>>();
+        eprintln!(
+            "Tick {}: latency {} ms (max {} ms, budget {} ms) histogram [{}]",
+            tick, latency.as_millis(), self.max_latency, self.budget, buckets.join(" "));
+    }
+}
@@ -0,0 +1,38 @@
+//! Save humans, destroy zombies!
+//! The agent, simulator and offline tooling, shared by the CodinGame binary and other tools.
+
+pub mod agent;
+pub mod book;
+#[cfg(feature = "local")]
+pub mod book_builder;
+pub mod bots;
+pub mod bounds;
+mod collections;
+pub mod config;
+pub mod evaluation;
+mod formatter;
+#[cfg(feature = "local")]
+pub mod generator;
+pub mod geometry;
+#[cfg(feature = "local")]
+pub mod golden;
+pub mod latency;
+mod milestones;
+pub mod model;
+pub mod mutations;
+mod openings;
+pub mod parser;
+#[cfg(feature = "local")]
+pub mod referee;
+pub mod rollouts;
+pub mod simulator;
+#[cfg(feature = "local")]
+pub mod tournament;
+pub mod tracker;
+mod validation;
+#[cfg(feature = "local")]
+pub mod tuner;
+pub mod verifier;
+
+pub use model::{Action, Event, Human, Milestone, Strategy, World, Zombie, V2};
+pub use config::AgentConfig;
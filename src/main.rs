@@ -1,52 +1,110 @@
-#![allow(dead_code)]
-
-const SCORE_SHEET_SIZE: usize = 10;
-
-mod agent;
-mod collections;
-mod evaluation;
-mod formatter;
-mod geometry;
-mod milestones;
-mod model;
-mod mutations;
-mod parser;
-mod rollouts;
-mod simulator;
-mod verifier;
-
-use rand;
-
-use model::*;
-use evaluation::ScoreParams;
-use verifier::Verifier;
+use std::env;
+use std::io;
+use std::io::Write;
+use std::time::Instant;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use codevszombies::*;
+use codevszombies::{bots, parser};
+#[cfg(feature = "local")]
+use codevszombies::{book_builder, generator, golden, referee, tournament, tuner};
+use codevszombies::latency::LatencyMonitor;
+use codevszombies::tracker::ScoreTracker;
+use codevszombies::verifier::Verifier;
 
 /**
  * Save humans, destroy zombies!
  **/
 fn main() {
-    let mut rng = rand::thread_rng();
+    let mut rng = StdRng::from_entropy();
+    let mut config = AgentConfig::new();
+    let mut bot_name = "pool".to_string();
 
-    let score_sheet = ScoreParams::gen_sheet(&mut rng);
-    let mut previous_strategies = Vec::<Strategy>::new();
+    // Offline usage: codevszombies [--config agent.cfg] [--set key=value] [--seed n] [--bot name] <command>
+    //   referee scenarios/*.txt
+    //   bless scenarios/*.txt
+    //   book src/openings.rs scenarios/*.txt
+    //   replay scenarios/01-simple.txt "[0] z0" "[1] (8250,4500) z0"
+    //   tune 100 scenarios/*.txt > tuned.cfg
+    //   tournament default tuned.cfg 5 results/tuned scenarios/*.txt
+    //   generate 20 scenarios/generated humans=5 zombies=10 clustering=0.3 zombie_distance=2000 ash=far doomed=0.2
+    let mut args = Vec::<String>::new();
+    let mut raw_args = env::args().skip(1);
+    while let Some(arg) = raw_args.next() {
+        match arg.as_str() {
+            "--config" => config = AgentConfig::load(&raw_args.next().unwrap_or_default()).unwrap_or_else(|error| panic!("{}", error)),
+            "--set" => config.apply(&raw_args.next().unwrap_or_default()).unwrap_or_else(|error| panic!("{}", error)),
+            "--bot" => bot_name = raw_args.next().unwrap_or_default(),
+            "--seed" => rng = StdRng::seed_from_u64(raw_args.next().unwrap_or_default().parse::<u64>().unwrap()),
+            _ => args.push(arg),
+        }
+    }
+
+    // Offline commands are left out of the submission bundle
+    #[cfg(feature = "local")]
+    match args.first().map(|arg| arg.as_str()) {
+        Some("referee") => return referee::run(&args[1..], &bot_name, &config, &mut rng),
+        Some("bless") => return golden::bless(&args[1..]),
+        Some("book") if args.len() > 1 => return book_builder::run(&args[1], &args[2..], &config, &mut rng),
+        Some("generate") if args.len() > 2 => return generator::run(args[1].parse::<u64>().unwrap(), &args[2], &args[3..]),
+        Some("tournament") if args.len() > 4 => {
+            let a = tournament::Contender::parse(&args[1], &config).unwrap_or_else(|error| panic!("{}", error));
+            let b = tournament::Contender::parse(&args[2], &config).unwrap_or_else(|error| panic!("{}", error));
+            return tournament::run(&a, &b, args[3].parse::<u64>().unwrap(), &args[4], &args[5..], &mut rng);
+        },
+        Some("replay") if args.len() > 1 => return referee::run_replay(&args[1], &args[2..]),
+        Some("tune") if args.len() > 1 => return tuner::run(args[1].parse::<i32>().unwrap(), &args[2..], &config, &mut rng),
+        _ => {},
+    }
+
+    let mut bot = bots::create(&bot_name, &config, rng).unwrap_or_else(|error| panic!("{}", error));
 
     // game loop
     let mut tick: i32 = 0;
     let mut verifier = Verifier::new();
+    let mut tracker = ScoreTracker::new();
+    let mut monitor = LatencyMonitor::new(&config);
     loop {
-        let world = parser::read_world(tick);
-        let strategies = agent::choose(&world, &score_sheet, previous_strategies, &mut rng);
-        let action = rollouts::strategy_to_action(&strategies[0], &world);
+        let world = match parser::read_world(tick) {
+            Ok(world) => world,
+            Err(error) => {
+                eprintln!("Tick {}: {}", tick, error);
+                return;
+            },
+        };
+        let start = Instant::now();
+        bot.set_budget(monitor.budget());
+        if tick == 0 {
+            bot.start(&world);
+        }
+
+        let gain = tracker.observe(&world);
+        eprintln!("Score: {} (+{})", tracker.score, gain);
+        verifier.log_score_error(&world, gain);
+
+        let action = bot.act(&world);
         // Write an action using println!("message...");
         // To debug: eprintln!("Debug message...");
 
         println!("{}", &action); // Your destination coordinates
+        io::stdout().flush().unwrap();
+
+        let latency = start.elapsed();
+        monitor.record(tick, latency);
+        monitor.log(tick, latency);
+        bot.log(&world);
 
         verifier.log_prediction_error(&world);
 
         // Update for next tick
         verifier.next(&world, &action);
-        previous_strategies = strategies;
+        if let Some(ending) = verifier.predicted_ending() {
+            let final_score = match ending {
+                Event::Lost { .. } => 0.0,
+                _ => tracker.score + verifier.predicted_gain(),
+            };
+            eprintln!("Game over predicted: {}, final score {}", ending, final_score);
+        }
         tick += 1;
     }
 }
@@ -1,35 +1,139 @@
-use super::*;
+use super::model::*;
+
+use std::cmp;
+use super::collections;
+use super::simulator;
 
 impl Milestone {
-    pub fn to_action(&self, world: &World) -> Option<Action> {
+    // elapsed is the number of ticks this milestone has already been emitting actions for
+    pub fn to_action(&self, world: &World, elapsed: i32) -> Option<Action> {
         match &self {
             Milestone::KillZombie { zombie_id } => kill_zombie_to_action(*zombie_id, world),
-            Milestone::ProtectHuman { human_id } => protect_human_to_action(*human_id, world),
+            Milestone::InterceptZombie { zombie_id } => intercept_zombie_to_action(*zombie_id, world),
+            Milestone::ComboAt { zombie_ids } => combo_at_to_action(zombie_ids, world),
+            Milestone::ProtectHuman { human_id, until } => protect_human_to_action(*human_id, *until, world, elapsed),
             Milestone::MoveTo { target } => move_to_action(*target, world),
+            Milestone::MoveNear { anchor, offset } => move_near_action(anchor, *offset, world),
+            Milestone::Wait { ticks } => wait_to_action(*ticks, world, elapsed),
+            Milestone::WaitUntilZombiesWithin { count, radius } => wait_until_zombies_within_to_action(*count, *radius, world),
+        }
+    }
+
+    // The equivalent milestone after it has been emitting actions for elapsed ticks, so it can be carried over to the next tick
+    pub fn after(&self, elapsed: i32) -> Milestone {
+        match &self {
+            Milestone::Wait { ticks } => Milestone::Wait { ticks: cmp::max(0, ticks - elapsed) },
+            Milestone::ProtectHuman { human_id, until: GuardCondition::Ticks(ticks) } => Milestone::ProtectHuman {
+                human_id: *human_id,
+                until: GuardCondition::Ticks(cmp::max(0, ticks - elapsed)),
+            },
+            _ => self.clone(),
+        }
+    }
+}
+
+impl Anchor {
+    pub fn resolve(&self, world: &World) -> Option<V2> {
+        match &self {
+            Anchor::Human(human_id) => world.humans.get(human_id).map(|human| human.pos),
+            Anchor::Zombie(zombie_id) => world.zombies.get(zombie_id).map(|zombie| zombie.next),
+            Anchor::Centroid(zombie_ids) => {
+                let positions = zombie_ids.iter().filter_map(|zombie_id| world.zombies.get(zombie_id)).map(|zombie| zombie.next).collect::<Vec<V2>>();
+                if positions.is_empty() {
+                    None
+                } else {
+                    Some(positions.iter().fold(V2::zero(), |sum, pos| sum.add(*pos)).div(positions.len() as f32))
+                }
+            },
         }
     }
 }
 
 fn kill_zombie_to_action(zombie_id: i32, world: &World) -> Option<Action> {
-    match world.zombies.get(&zombie_id) {
-        Some(zombie) => Some(Action { target: zombie.next }),
-        None => None,
+    world.zombies.get(&zombie_id).map(|zombie| Action { target: zombie.next })
+}
+
+fn intercept_zombie_to_action(zombie_id: i32, world: &World) -> Option<Action> {
+    const MAX_INTERCEPT_TICKS: usize = 50;
+
+    let zombie = world.zombies.get(&zombie_id)?;
+
+    // Project the zombie towards its current target and meet it at the first point Ash can reach in time
+    let path = simulator::project_zombie(zombie, world, MAX_INTERCEPT_TICKS);
+    for (i, pos) in path.iter().enumerate() {
+        let reach = ((i + 1) as f32) * constants::MAX_ASH_STEP + constants::MAX_ASH_KILL_RANGE;
+        if world.ash.pos.distance_to(*pos) <= reach {
+            return Some(Action { target: *pos });
+        }
     }
+
+    Some(Action { target: zombie.next }) // Cannot catch it, just chase
 }
 
-fn protect_human_to_action(human_id: i32, world: &World) -> Option<Action> {
-    const PRECISION: f32 = 1.0;
+fn combo_at_to_action(zombie_ids: &[i32], world: &World) -> Option<Action> {
+    const MAX_COMBO_TICKS: usize = 20;
+
+    let zombies = zombie_ids.iter().filter_map(|zombie_id| world.zombies.get(zombie_id)).collect::<Vec<&Zombie>>();
+    if zombies.is_empty() { return None; }
 
-    match world.humans.get(&human_id) {
-        Some(human) => {
-            let distance = world.ash.pos.distance_to(human.pos);
-            if distance < PRECISION {
-                None // Already at human, stop and move to next milestone
-            } else {
-                Some(Action { target: human.pos })
+    let paths = zombies.iter().map(|zombie| simulator::project_zombie(zombie, world, MAX_COMBO_TICKS)).collect::<Vec<_>>();
+    let kill_range_squared = constants::MAX_ASH_KILL_RANGE.powf(2.0);
+
+    // Find the reachable point which covers the most zombies on arrival, preferring earlier arrivals
+    let mut best_covered = 0;
+    let mut best_target: Option<V2> = None;
+    for tick in 0..MAX_COMBO_TICKS {
+        let positions = paths.iter().map(|path| path[tick]).collect::<Vec<V2>>();
+        let reach = ((tick + 1) as f32) * constants::MAX_ASH_STEP;
+
+        let centroid = positions.iter().fold(V2::zero(), |sum, pos| sum.add(*pos)).div(positions.len() as f32);
+        for candidate in std::iter::once(centroid).chain(positions.iter().cloned()) {
+            if world.ash.pos.distance_to(candidate) > reach { continue; }
+
+            let num_covered = positions.iter().filter(|pos| pos.distance_to_squared(candidate) <= kill_range_squared).count();
+            if num_covered > best_covered {
+                best_covered = num_covered;
+                best_target = Some(candidate);
             }
+        }
+
+        if best_covered == zombies.len() { break; }
+    }
+
+    match best_target {
+        Some(target) => Some(Action { target }),
+        None => Some(Action { target: zombies[0].next }), // Nothing reachable yet, close in on the cluster
+    }
+}
+
+fn protect_human_to_action(human_id: i32, until: GuardCondition, world: &World, elapsed: i32) -> Option<Action> {
+    const NEAR_RADIUS: f32 = 2.0 * constants::MAX_ASH_KILL_RANGE;
+
+    let human = world.humans.get(&human_id)?;
+
+    let threats = world.zombies.values().filter(|zombie| {
+        simulator::find_zombie_target(zombie.pos, &world.ash, &world.humans).distance_to(human.pos) <= 0.0
+    }).collect::<Vec<&Zombie>>();
+
+    let complete = match until {
+        GuardCondition::Ticks(ticks) => elapsed >= ticks,
+        GuardCondition::ZombiesNear(count) => {
+            let near_radius_squared = NEAR_RADIUS.powf(2.0);
+            world.zombies.values().filter(|zombie| zombie.pos.distance_to_squared(human.pos) <= near_radius_squared).count() <= count
         },
-        None => None,
+        GuardCondition::ThreatCleared => threats.is_empty(),
+    };
+    if complete { return None; }
+
+    // Stand between the human and the most urgent zombie, close enough to the human to shoot anything reaching it
+    let candidates = if threats.is_empty() { world.zombies.values().collect::<Vec<&Zombie>>() } else { threats };
+    match collections::min_by_fkey(&candidates, |zombie| zombie.pos.distance_to(human.pos)) {
+        Some(zombie) => {
+            let offset = V2::diff(zombie.next, human.pos);
+            let guard_distance = (offset.length() / 2.0).min(constants::MAX_ASH_KILL_RANGE);
+            Some(Action { target: human.pos.add(offset.unit().mul(guard_distance)) })
+        },
+        None => Some(Action { target: human.pos }),
     }
 }
 
@@ -41,4 +145,27 @@ fn move_to_action(target: V2, world: &World) -> Option<Action> {
     } else {
         Some(Action { target })
     }
+}
+
+fn move_near_action(anchor: &Anchor, offset: V2, world: &World) -> Option<Action> {
+    let target = anchor.resolve(world)?.add(offset).clamp_to_map();
+    move_to_action(target, world)
+}
+
+fn wait_to_action(ticks: i32, world: &World, elapsed: i32) -> Option<Action> {
+    if elapsed < ticks {
+        Some(Action { target: world.ash.pos })
+    } else {
+        None
+    }
+}
+
+fn wait_until_zombies_within_to_action(count: usize, radius: f32, world: &World) -> Option<Action> {
+    let radius_squared = radius.powf(2.0);
+    let num_within = world.zombies.values().filter(|zombie| zombie.pos.distance_to_squared(world.ash.pos) <= radius_squared).count();
+    if num_within < cmp::min(count, world.zombies.len()) {
+        Some(Action { target: world.ash.pos })
+    } else {
+        None
+    }
 }
\ No newline at end of file
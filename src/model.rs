@@ -1,5 +1,7 @@
 pub use super::geometry::*;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 
 pub mod constants {
     pub const MAP_WIDTH: i32 = 16000;
@@ -10,6 +12,16 @@ pub mod constants {
     pub const MAX_ZOMBIE_KILL_RANGE: f32 = 0.0001;
 }
 
+impl V2 {
+    // The nearest point Ash can stand on
+    pub fn clamp_to_map(self) -> V2 {
+        V2 {
+            x: self.x.max(0.0).min((constants::MAP_WIDTH - 1) as f32),
+            y: self.y.max(0.0).min((constants::MAP_HEIGHT - 1) as f32),
+        }
+    }
+}
+
 pub trait Positioned {
     fn pos(&self) -> V2;
 }
@@ -54,8 +66,14 @@ impl Positioned for Ash {
 pub struct World {
     pub tick: i32,
     pub ash: Ash,
-    pub humans: HashMap<i32, Human>,
-    pub zombies: HashMap<i32, Zombie>,
+    pub humans: BTreeMap<i32, Human>,
+    pub zombies: BTreeMap<i32, Zombie>,
+}
+
+impl Default for World {
+    fn default() -> World {
+        World::new()
+    }
 }
 
 impl World {
@@ -63,8 +81,8 @@ impl World {
         World {
             tick: 0,
             ash: Ash { pos: V2::zero() },
-            humans: HashMap::new(),
-            zombies: HashMap::new(),
+            humans: BTreeMap::new(),
+            zombies: BTreeMap::new(),
         }
     }
 }
@@ -74,16 +92,70 @@ pub struct Action {
     pub target: V2,
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub enum Milestone {
     KillZombie { zombie_id: i32 },
-    ProtectHuman { human_id: i32 },
+    InterceptZombie { zombie_id: i32 },
+    ComboAt { zombie_ids: Vec<i32> },
+    ProtectHuman { human_id: i32, until: GuardCondition },
     MoveTo { target: V2 },
+    MoveNear { anchor: Anchor, offset: V2 },
+    Wait { ticks: i32 },
+    WaitUntilZombiesWithin { count: usize, radius: f32 },
+}
+
+#[derive(Clone, PartialEq, Hash)]
+pub enum Anchor {
+    Human(i32),
+    Zombie(i32),
+    Centroid(Vec<i32>), // of zombies
+}
+
+#[derive(Clone, Copy, PartialEq, Hash)]
+pub enum GuardCondition {
+    Ticks(i32),
+    ZombiesNear(usize),
+    ThreatCleared,
 }
 
 impl Milestone {
     pub fn is_move(&self) -> bool {
-        match self { Milestone::MoveTo{..} => true, _ => false }
+        matches!(self, Milestone::MoveTo{..})
+    }
+
+    pub fn is_anchored_move(&self) -> bool {
+        matches!(self, Milestone::MoveNear{..})
+    }
+
+    pub fn is_wait(&self) -> bool {
+        matches!(self, Milestone::Wait{..} | Milestone::WaitUntilZombiesWithin{..})
+    }
+
+    pub fn is_guard(&self) -> bool {
+        matches!(self, Milestone::ProtectHuman{..})
+    }
+
+    pub fn attacks_zombie(&self) -> Option<i32> {
+        match self {
+            Milestone::KillZombie { zombie_id } => Some(*zombie_id),
+            Milestone::InterceptZombie { zombie_id } => Some(*zombie_id),
+            _ => None,
+        }
+    }
+}
+
+impl Hash for Milestone {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Milestone::KillZombie { zombie_id } | Milestone::InterceptZombie { zombie_id } => zombie_id.hash(state),
+            Milestone::ComboAt { zombie_ids } => zombie_ids.hash(state),
+            Milestone::ProtectHuman { human_id, until } => { human_id.hash(state); until.hash(state); },
+            Milestone::MoveTo { target } => target.hash(state),
+            Milestone::MoveNear { anchor, offset } => { anchor.hash(state); offset.hash(state); },
+            Milestone::Wait { ticks } => ticks.hash(state),
+            Milestone::WaitUntilZombiesWithin { count, radius } => { count.hash(state); hash_f32(*radius, state); },
+        }
     }
 }
 
@@ -108,6 +180,13 @@ impl Strategy {
     pub fn is_empty(&self) -> bool {
         self.milestones.len() == 0
     }
+
+    pub fn fingerprint(&self) -> u64 {
+        // Identifies the milestone list, ignoring the strategy ID
+        let mut hasher = DefaultHasher::new();
+        self.milestones.hash(&mut hasher);
+        hasher.finish()
+    }
 }
 
 #[derive(Clone)]
@@ -120,10 +199,6 @@ pub enum Event {
 
 impl Event {
     pub fn is_ending(&self) -> bool {
-        match &self {
-            Event::Won{..} => true,
-            Event::Lost{..} => true,
-            _ => false,
-        }
+        matches!(self, Event::Won{..} | Event::Lost{..})
     }
 }
\ No newline at end of file
@@ -1,55 +1,114 @@
 pub use super::model::*;
 
 use std::cmp;
+use std::fmt;
 use std::collections::HashSet;
 use std::ops;
-use rand;
 use rand::Rng;
+use rand::rngs::StdRng;
+use super::config::AgentConfig;
+
+type Operator = fn(&mut Strategy, &World, &mut StdRng) -> bool;
+type Proportion = fn(&AgentConfig) -> f32;
+
+// Tried in order until one applies, each with its configured probability
+pub const OPERATORS: &[(&str, Proportion, Operator)] = &[
+    ("bump_move", |config| config.bump_move_proportion, |strategy, _, rng| bump_move(strategy, rng)),
+    ("replace_move", |config| config.replace_move_proportion, |strategy, _, rng| replace_move(strategy, rng)),
+    ("anchored_move", |config| config.anchored_move_proportion, |strategy, world, rng| insert_anchored_move(world, strategy, rng)),
+    ("bump_offset", |config| config.bump_offset_proportion, |strategy, _, rng| bump_offset(strategy, rng)),
+
+    ("drop", |config| config.drop_proportion, |strategy, _, rng| drop_element(strategy, rng)),
+
+    ("attack_zombie", |config| config.attack_zombie_proportion, |strategy, world, rng| insert_attack(world, strategy, rng)),
+    ("intercept_zombie", |config| config.intercept_zombie_proportion, |strategy, world, rng| insert_intercept(world, strategy, rng)),
+    ("switch_attack", |config| config.switch_attack_proportion, |strategy, _, rng| switch_attack(strategy, rng)),
+    ("combo", |config| config.combo_proportion, |strategy, world, rng| insert_combo(world, strategy, rng)),
+    ("protect_human", |config| config.protect_human_proportion, |strategy, world, rng| insert_defend(world, strategy, rng)),
+    ("tune_guard", |config| config.tune_guard_proportion, |strategy, world, rng| tune_guard(world, strategy, rng)),
+
+    ("wait", |config| config.wait_proportion, |strategy, world, rng| insert_wait(world, strategy, rng)),
+    ("adjust_wait", |config| config.adjust_wait_proportion, |strategy, world, rng| adjust_wait(world, strategy, rng)),
+
+    ("bubble", |config| config.bubble_proportion, |strategy, _, rng| bubble_elements(strategy, rng)),
+    ("swap", |config| config.swap_proportion, |strategy, _, rng| swap_elements(strategy, rng)),
+    ("displace", |config| config.displace_proportion, |strategy, world, rng| displace_section(world, strategy, rng)),
+];
+
+// How often each operator applied but left the strategy unchanged once normalized
+pub struct MutationStats {
+    applied: Vec<usize>,
+    no_ops: Vec<usize>,
+}
 
-const REPLACE_MOVE_PROPORTION: f32 = 0.5;
-const BUMP_MOVE_PROPORTION: f32 = 0.25;
-
-const ATTACK_ZOMBIE_PROPORTION: f32 = 0.1;
-const PROTECT_HUMAN_PROPORTION: f32 = 0.1;
-const DROP_PROPORTION: f32 = 0.05;
-
-const BUBBLE_PROPORTION: f32 = 0.1;
-const SWAP_PROPORTION: f32 = 0.05;
-const DISPLACE_PROPORTION: f32 = 0.5;
-
-pub fn mutate_strategy(strategy: &mut Strategy, world: &World, rng: &mut rand::prelude::ThreadRng) -> bool {
-    let mut mutated = false;
+impl MutationStats {
+    pub fn new() -> MutationStats {
+        MutationStats {
+            applied: vec![0; OPERATORS.len()],
+            no_ops: vec![0; OPERATORS.len()],
+        }
+    }
 
-    if !mutated && rng.gen::<f32>() < BUMP_MOVE_PROPORTION { mutated = bump_move(strategy, rng); }
-    if !mutated && rng.gen::<f32>() < REPLACE_MOVE_PROPORTION { mutated = replace_move(strategy, rng); }
+    pub fn no_op_rate(&self, operator_index: usize) -> Option<f32> {
+        if self.applied[operator_index] == 0 { return None; }
+        Some(self.no_ops[operator_index] as f32 / self.applied[operator_index] as f32)
+    }
+}
 
-    if !mutated && rng.gen::<f32>() < DROP_PROPORTION { mutated = drop_element(strategy, rng); }
+impl Default for MutationStats {
+    fn default() -> MutationStats {
+        MutationStats::new()
+    }
+}
 
-    if !mutated && rng.gen::<f32>() < ATTACK_ZOMBIE_PROPORTION { mutated = insert_attack(world, strategy, rng); }
-    if !mutated && rng.gen::<f32>() < PROTECT_HUMAN_PROPORTION { mutated = insert_defend(world, strategy, rng); }
+impl fmt::Display for MutationStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, _, _)) in OPERATORS.iter().enumerate() {
+            if self.applied[i] == 0 { continue; }
+            write!(f, "{} {}/{} ", name, self.no_ops[i], self.applied[i])?;
+        }
+        Ok(())
+    }
+}
 
-    if !mutated && rng.gen::<f32>() < BUBBLE_PROPORTION { mutated = bubble_elements(strategy, rng); }
-    if !mutated && rng.gen::<f32>() < SWAP_PROPORTION { mutated = swap_elements(strategy, rng); }
-    if !mutated && rng.gen::<f32>() < DISPLACE_PROPORTION { mutated = displace_section(world, strategy, rng); }
+pub fn mutate_strategy(strategy: &mut Strategy, world: &World, config: &AgentConfig, rng: &mut StdRng, stats: &mut MutationStats) -> bool {
+    for (i, (_, proportion, _)) in OPERATORS.iter().enumerate() {
+        if rng.gen::<f32>() < proportion(config) {
+            let previous = strategy.milestones.clone();
+            if apply(i, strategy, world, rng) {
+                stats.applied[i] += 1;
+                if strategy.milestones == previous {
+                    stats.no_ops[i] += 1;
+                }
+                return true;
+            }
+        }
+    }
+    false
+}
 
-    mutated
+pub fn apply(operator_index: usize, strategy: &mut Strategy, world: &World, rng: &mut StdRng) -> bool {
+    // Operators reorder and insert freely, so clean up whatever they leave behind
+    let (_, _, operator) = OPERATORS[operator_index];
+    let applied = operator(strategy, world, rng);
+    if applied {
+        strategy.normalize(world);
+    }
+    applied
 }
 
-fn bump_move(strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) -> bool {
+fn bump_move(strategy: &mut Strategy, rng: &mut StdRng) -> bool {
     const MUTATE_RADIUS: f32 = constants::MAX_ASH_STEP + constants::MAX_ASH_KILL_RANGE + 1.0; // Be able to step away from killing something
 
     match choose_move_index(strategy, rng) {
         Some(move_index) => {
-            match strategy.milestones[move_index] {
-                Milestone::MoveTo { target: previous } => {
-                    strategy.milestones[move_index] = Milestone::MoveTo {
-                        target: V2 {
-                            x: clamp(previous.x + rng.gen_range(-MUTATE_RADIUS..MUTATE_RADIUS), 0.0, constants::MAP_WIDTH as f32),
-                            y: clamp(previous.y + rng.gen_range(-MUTATE_RADIUS..MUTATE_RADIUS), 0.0, constants::MAP_HEIGHT as f32),
-                        },
-                    }
-                },
-                _ => {},
+            if let Milestone::MoveTo { target: previous } = strategy.milestones[move_index] {
+                strategy.milestones[move_index] = Milestone::MoveTo {
+                    target: V2 {
+                        x: previous.x + rng.gen_range(-MUTATE_RADIUS..MUTATE_RADIUS),
+                        y: previous.y + rng.gen_range(-MUTATE_RADIUS..MUTATE_RADIUS),
+                    }.clamp_to_map(),
+                }
             };
             true
         },
@@ -57,7 +116,7 @@ fn bump_move(strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) -> boo
     }
 }
 
-fn replace_move(strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) -> bool {
+fn replace_move(strategy: &mut Strategy, rng: &mut StdRng) -> bool {
     const KEEP_PROBABILITY: f32 = 0.95;
 
     // Drop random number of items
@@ -75,8 +134,54 @@ fn replace_move(strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) ->
 
     true
 }
-fn drop_element(strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) -> bool {
-    if strategy.milestones.len() == 0 { return false; }
+
+fn insert_anchored_move(world: &World, strategy: &mut Strategy, rng: &mut StdRng) -> bool {
+    const OFFSET_RADIUS: f32 = constants::MAX_ASH_KILL_RANGE;
+
+    let anchor = match rng.gen_range(0..3) {
+        0 if !world.humans.is_empty() => Anchor::Human(*world.humans.keys().nth(rng.gen_range(0..world.humans.len())).unwrap()),
+        1 if !world.zombies.is_empty() => Anchor::Zombie(*world.zombies.keys().nth(rng.gen_range(0..world.zombies.len())).unwrap()),
+        _ if world.zombies.len() >= 2 => {
+            let mut zombie_ids = world.zombies.keys().copied().filter(|_| rng.gen::<bool>()).collect::<Vec<i32>>();
+            if zombie_ids.is_empty() { return false; }
+            zombie_ids.sort_unstable();
+            Anchor::Centroid(zombie_ids)
+        },
+        _ => return false,
+    };
+
+    let offset = V2 {
+        x: rng.gen_range(-OFFSET_RADIUS..OFFSET_RADIUS),
+        y: rng.gen_range(-OFFSET_RADIUS..OFFSET_RADIUS),
+    };
+    let insert_index = rng.gen_range(0 .. (strategy.milestones.len() + 1)); // +1 because can add at end of vec
+    strategy.milestones.insert(insert_index, Milestone::MoveNear { anchor, offset });
+
+    true
+}
+
+fn bump_offset(strategy: &mut Strategy, rng: &mut StdRng) -> bool {
+    const MUTATE_RADIUS: f32 = constants::MAX_ASH_STEP;
+
+    let anchored_indices = (0..strategy.milestones.len()).filter(|i| strategy.milestones[*i].is_anchored_move()).collect::<Vec<usize>>();
+    if anchored_indices.is_empty() { return false; }
+
+    let index = anchored_indices[rng.gen_range(0..anchored_indices.len())];
+    if let Milestone::MoveNear { anchor, offset } = &strategy.milestones[index] {
+        strategy.milestones[index] = Milestone::MoveNear {
+            anchor: anchor.clone(),
+            offset: V2 {
+                x: offset.x + rng.gen_range(-MUTATE_RADIUS..MUTATE_RADIUS),
+                y: offset.y + rng.gen_range(-MUTATE_RADIUS..MUTATE_RADIUS),
+            },
+        };
+    }
+
+    true
+}
+
+fn drop_element(strategy: &mut Strategy, rng: &mut StdRng) -> bool {
+    if strategy.milestones.is_empty() { return false; }
 
     let drop_index = rng.gen_range(0..strategy.milestones.len());
     strategy.milestones.remove(drop_index);
@@ -84,7 +189,7 @@ fn drop_element(strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) ->
     true
 }
 
-fn choose_move_index(strategy: &Strategy, rng: &mut rand::prelude::ThreadRng) -> Option<usize> {
+fn choose_move_index(strategy: &Strategy, rng: &mut StdRng) -> Option<usize> {
     let num_moves = strategy.milestones.iter().filter(|m| m.is_move()).count();
     if num_moves == 0 { return None }
 
@@ -102,44 +207,178 @@ fn choose_move_index(strategy: &Strategy, rng: &mut rand::prelude::ThreadRng) ->
     None
 }
 
-fn insert_attack(world: &World, strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) -> bool {
-    if world.zombies.len() == 0 { return false }
+fn insert_attack(world: &World, strategy: &mut Strategy, rng: &mut StdRng) -> bool {
+    match choose_unattacked_zombie(world, strategy, rng) {
+        Some(zombie_id) => {
+            let insert_index = rng.gen_range(0 .. (strategy.milestones.len() + 1)); // +1 because can add at end of vec
+            strategy.milestones.insert(insert_index, Milestone::KillZombie {
+                zombie_id,
+            });
+            true
+        },
+        None => false,
+    }
+}
 
-    let mut zombie_ids = world.zombies.keys().map(|k| *k).collect::<HashSet<i32>>();
-    for milestone in strategy.milestones.iter() { // Remove zombie IDs that we're already attacking
-        match milestone {
-            Milestone::KillZombie { zombie_id } => { zombie_ids.remove(zombie_id); },
-            _ => (),
-        }
+fn insert_intercept(world: &World, strategy: &mut Strategy, rng: &mut StdRng) -> bool {
+    match choose_unattacked_zombie(world, strategy, rng) {
+        Some(zombie_id) => {
+            let insert_index = rng.gen_range(0 .. (strategy.milestones.len() + 1)); // +1 because can add at end of vec
+            strategy.milestones.insert(insert_index, Milestone::InterceptZombie {
+                zombie_id,
+            });
+            true
+        },
+        None => false,
     }
-    if zombie_ids.len() == 0 { return false }
+}
 
-    let zombie_id = *zombie_ids.iter().nth(rng.gen_range(0..zombie_ids.len())).unwrap();
-    let insert_index = rng.gen_range(0 .. (strategy.milestones.len() + 1)); // +1 because can add at end of vec
+fn choose_unattacked_zombie(world: &World, strategy: &Strategy, rng: &mut StdRng) -> Option<i32> {
+    if world.zombies.is_empty() { return None }
 
-    strategy.milestones.insert(insert_index, Milestone::KillZombie {
-        zombie_id,
-    });
+    // Skip zombie IDs that we're already attacking
+    let attacked = strategy.milestones.iter().filter_map(|milestone| milestone.attacks_zombie()).collect::<HashSet<i32>>();
+    let zombie_ids = world.zombies.keys().copied().filter(|zombie_id| !attacked.contains(zombie_id)).collect::<Vec<i32>>();
+    if zombie_ids.is_empty() { return None }
+
+    Some(zombie_ids[rng.gen_range(0..zombie_ids.len())])
+}
+
+fn switch_attack(strategy: &mut Strategy, rng: &mut StdRng) -> bool {
+    // Toggle between chasing and intercepting the same zombie
+    let attack_indices = (0..strategy.milestones.len()).filter(|i| strategy.milestones[*i].attacks_zombie().is_some()).collect::<Vec<usize>>();
+    if attack_indices.is_empty() { return false; }
+
+    let index = attack_indices[rng.gen_range(0..attack_indices.len())];
+    strategy.milestones[index] = match strategy.milestones[index] {
+        Milestone::KillZombie { zombie_id } => Milestone::InterceptZombie { zombie_id },
+        Milestone::InterceptZombie { zombie_id } => Milestone::KillZombie { zombie_id },
+        ref other => other.clone(),
+    };
 
     true
 }
 
-fn insert_defend(world: &World, strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) -> bool {
-    if world.humans.len() == 0 { return false; }
+fn insert_combo(world: &World, strategy: &mut Strategy, rng: &mut StdRng) -> bool {
+    const CLUSTER_RADIUS: f32 = 2.0 * constants::MAX_ASH_KILL_RANGE;
+
+    // Seed clusters from dense areas: a zombie is picked in proportion to how many neighbours it has
+    let zombies = world.zombies.values().collect::<Vec<&Zombie>>();
+    let neighbours = zombies.iter().map(|zombie| {
+        zombies.iter().filter(|other| other.id != zombie.id && other.next.distance_to(zombie.next) <= CLUSTER_RADIUS).map(|other| other.id).collect::<Vec<i32>>()
+    }).collect::<Vec<Vec<i32>>>();
+
+    let total_weight = neighbours.iter().map(|ids| ids.len()).sum::<usize>();
+    if total_weight == 0 { return false; }
+
+    let mut selector = rng.gen_range(0..total_weight);
+    for (i, ids) in neighbours.iter().enumerate() {
+        if selector < ids.len() {
+            let mut zombie_ids = vec![zombies[i].id];
+            zombie_ids.extend(ids.iter());
+            zombie_ids.sort_unstable();
+
+            let insert_index = rng.gen_range(0 .. (strategy.milestones.len() + 1)); // +1 because can add at end of vec
+            strategy.milestones.insert(insert_index, Milestone::ComboAt { zombie_ids });
+            return true;
+        }
+        selector -= ids.len();
+    }
+
+    false
+}
+
+fn insert_defend(world: &World, strategy: &mut Strategy, rng: &mut StdRng) -> bool {
+    if world.humans.is_empty() { return false; }
 
     let human_index = rng.gen_range(0..world.humans.len());
     let human = world.humans.values().nth(human_index).unwrap();
 
     let insert_index = rng.gen_range(0 .. (strategy.milestones.len() + 1)); // +1 because can add at end of vec
 
-    strategy.milestones.insert(insert_index, Milestone::MoveTo{
-        target: human.pos,
+    strategy.milestones.insert(insert_index, Milestone::ProtectHuman {
+        human_id: human.id,
+        until: gen_guard_condition(world, rng),
     });
 
     true
 }
 
-fn bubble_elements(strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) -> bool {
+fn tune_guard(world: &World, strategy: &mut Strategy, rng: &mut StdRng) -> bool {
+    const MAX_TICKS_ADJUSTMENT: i32 = 3;
+    const REPLACE_CONDITION_PROBABILITY: f32 = 0.25;
+
+    let guard_indices = (0..strategy.milestones.len()).filter(|i| strategy.milestones[*i].is_guard()).collect::<Vec<usize>>();
+    if guard_indices.is_empty() { return false; }
+
+    let index = guard_indices[rng.gen_range(0..guard_indices.len())];
+    if let Milestone::ProtectHuman { human_id, until } = strategy.milestones[index] {
+        let until = if rng.gen::<f32>() < REPLACE_CONDITION_PROBABILITY {
+            gen_guard_condition(world, rng)
+        } else {
+            match until {
+                GuardCondition::Ticks(ticks) => GuardCondition::Ticks(cmp::max(1, ticks + rng.gen_range(-MAX_TICKS_ADJUSTMENT..(MAX_TICKS_ADJUSTMENT + 1)))),
+                GuardCondition::ZombiesNear(count) => GuardCondition::ZombiesNear(if count > 0 && rng.gen::<bool>() { count - 1 } else { count + 1 }),
+                GuardCondition::ThreatCleared => GuardCondition::ThreatCleared,
+            }
+        };
+        strategy.milestones[index] = Milestone::ProtectHuman { human_id, until };
+    }
+
+    true
+}
+
+fn gen_guard_condition(world: &World, rng: &mut StdRng) -> GuardCondition {
+    const MAX_GUARD_TICKS: i32 = 10;
+
+    match rng.gen_range(0..3) {
+        0 => GuardCondition::Ticks(rng.gen_range(1..(MAX_GUARD_TICKS + 1))),
+        1 => GuardCondition::ZombiesNear(rng.gen_range(0..(world.zombies.len() + 1))),
+        _ => GuardCondition::ThreatCleared,
+    }
+}
+
+fn insert_wait(world: &World, strategy: &mut Strategy, rng: &mut StdRng) -> bool {
+    const MAX_WAIT_TICKS: i32 = 10;
+
+    let milestone = if world.zombies.len() >= 2 && rng.gen::<f32>() < 0.5 {
+        Milestone::WaitUntilZombiesWithin {
+            count: rng.gen_range(2..(world.zombies.len() + 1)),
+            radius: constants::MAX_ASH_KILL_RANGE,
+        }
+    } else {
+        Milestone::Wait { ticks: rng.gen_range(1..(MAX_WAIT_TICKS + 1)) }
+    };
+
+    let insert_index = rng.gen_range(0 .. (strategy.milestones.len() + 1)); // +1 because can add at end of vec
+    strategy.milestones.insert(insert_index, milestone);
+
+    true
+}
+
+fn adjust_wait(world: &World, strategy: &mut Strategy, rng: &mut StdRng) -> bool {
+    const MAX_TICKS_ADJUSTMENT: i32 = 3;
+    const MAX_RADIUS_ADJUSTMENT: f32 = 0.25;
+
+    let wait_indices = (0..strategy.milestones.len()).filter(|i| strategy.milestones[*i].is_wait()).collect::<Vec<usize>>();
+    if wait_indices.is_empty() { return false; }
+
+    let index = wait_indices[rng.gen_range(0..wait_indices.len())];
+    strategy.milestones[index] = match strategy.milestones[index] {
+        Milestone::Wait { ticks } => Milestone::Wait {
+            ticks: cmp::max(1, ticks + rng.gen_range(-MAX_TICKS_ADJUSTMENT..(MAX_TICKS_ADJUSTMENT + 1))),
+        },
+        Milestone::WaitUntilZombiesWithin { count, radius } => Milestone::WaitUntilZombiesWithin {
+            count: cmp::max(1, cmp::min(world.zombies.len() as i32, count as i32 + if rng.gen::<bool>() { 1 } else { -1 })) as usize,
+            radius: radius * (1.0 + rng.gen_range(-MAX_RADIUS_ADJUSTMENT..MAX_RADIUS_ADJUSTMENT)),
+        },
+        ref other => other.clone(),
+    };
+
+    true
+}
+
+fn bubble_elements(strategy: &mut Strategy, rng: &mut StdRng) -> bool {
     if strategy.milestones.len() < 2 { return false; }
 
     let bubble_index = rng.gen_range(0..(strategy.milestones.len() - 1));
@@ -148,7 +387,7 @@ fn bubble_elements(strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng)
     true
 }
 
-fn swap_elements(strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) -> bool {
+fn swap_elements(strategy: &mut Strategy, rng: &mut StdRng) -> bool {
     if strategy.milestones.len() < 2 { return false; }
 
     let from_index = rng.gen_range(0..(strategy.milestones.len() - 1));
@@ -162,7 +401,7 @@ fn swap_elements(strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) ->
     true
 }
 
-fn displace_section(world: &World, strategy: &mut Strategy, rng: &mut rand::prelude::ThreadRng) -> bool {
+fn displace_section(world: &World, strategy: &mut Strategy, rng: &mut StdRng) -> bool {
     let range_random = RangeRandom { max_length: cmp::min(world.zombies.len(), 10), power: 2.0 };
 
     if strategy.milestones.len() < 2 { return false; }
@@ -178,25 +417,19 @@ fn displace_section(world: &World, strategy: &mut Strategy, rng: &mut rand::prel
     if reverse {
         strategy.milestones.splice(displace_to_index .. displace_to_index, displaced.into_iter().rev());
     } else {
-        strategy.milestones.splice(displace_to_index .. displace_to_index, displaced.into_iter());
+        strategy.milestones.splice(displace_to_index .. displace_to_index, displaced);
     }
 
     true
 }
 
-fn clamp(v: f32, min_value: f32, max_value: f32) -> f32 {
-    if v < min_value { min_value }
-    else if v > max_value { max_value }
-    else { v }
-}
-
 struct RangeRandom {
     max_length: usize,
     power: f32,
 }
 
 impl RangeRandom {
-    pub fn gen(&self, range: ops::Range<usize>, rng: &mut rand::prelude::ThreadRng) -> usize {
+    pub fn gen(&self, range: ops::Range<usize>, rng: &mut StdRng) -> usize {
         let base = (rng.gen::<f32>().powf(self.power) * self.max_length as f32) as usize;
         cmp::min(range.start + base, range.end - 1)
     }
@@ -0,0 +1,7 @@
+// Generated by `codevszombies book src/openings.rs scenarios/*.txt` - do not edit by hand
+pub const OPENINGS: &[(u64, &str)] = &[
+    (0xfa4e53f03dae078c, "[0] i0"), // scenarios/01-simple.txt: 10
+    (0x824912327cca54ce, "[0] @z0(811.55786,1051.4592) z1"), // scenarios/02-two-zombies.txt: 80
+    (0xa90c9292549dbd6b, "[0] i0 z1"), // scenarios/03-two-zombies-redux.txt: 80
+    (0xe09e4e0195c65b2d, "[0] @c0+1(137.21533,631.6547)"), // scenarios/04-scared-human.txt: 30
+];
@@ -1,73 +1,180 @@
 use std::io;
-use std::collections::HashMap;
+use std::io::BufRead;
+use std::str::FromStr;
+use std::collections::BTreeMap;
 use super::model::*;
 
 macro_rules! parse_input {
-    ($x:expr, $t:ident) => ($x.trim().parse::<$t>().unwrap())
+    ($x:expr, $t:ident) => ($x.trim().parse::<$t>().map_err(|_| format!("Expected {}: {:?}", stringify!($t), $x.trim()))?)
 }
 
-pub fn read_world(tick: i32) -> World {
-    let input_line = read_line();
-    let inputs = split_line(&input_line);
-    let pos = parse_v2(inputs[0], inputs[1]);
-
-    let input_line = read_line();
-    let human_count = parse_input!(input_line, i32);
-    let mut humans = HashMap::<i32, Human>::new();
-    for _ in 0..human_count as usize {
-        let input_line = read_line();
-        let human = parse_human(&input_line);
+pub fn read_world(tick: i32) -> Result<World, String> {
+    read_world_from(&mut io::stdin().lock(), tick)
+}
+
+// Malformed input is reported rather than panicking, so the referee's line can be diagnosed
+pub fn read_world_from(input: &mut impl BufRead, tick: i32) -> Result<World, String> {
+    let input_line = read_line(input)?;
+    let inputs = split_line(&input_line, 2)?;
+    let pos = parse_v2(inputs[0], inputs[1])?;
+
+    let input_line = read_line(input)?;
+    let human_count = parse_input!(input_line, usize);
+    let mut humans = BTreeMap::<i32, Human>::new();
+    for _ in 0..human_count {
+        let input_line = read_line(input)?;
+        let human = parse_human(&input_line)?;
         humans.insert(human.id, human);
     }
 
-    let mut input_line = String::new();
-    io::stdin().read_line(&mut input_line).unwrap();
-    let zombie_count = parse_input!(input_line, i32);
-    let mut zombies = HashMap::<i32, Zombie>::new();
-    for _ in 0..zombie_count as usize {
-        let input_line = read_line();
-        let zombie = parse_zombie(&input_line);
+    let input_line = read_line(input)?;
+    let zombie_count = parse_input!(input_line, usize);
+    let mut zombies = BTreeMap::<i32, Zombie>::new();
+    for _ in 0..zombie_count {
+        let input_line = read_line(input)?;
+        let zombie = parse_zombie(&input_line)?;
         zombies.insert(zombie.id, zombie);
     }
 
-    World {
+    Ok(World {
         tick,
         ash: Ash { pos },
         humans,
         zombies,
-    }
+    })
 }
 
-fn read_line() -> String {
+fn read_line(input: &mut impl BufRead) -> Result<String, String> {
     let mut input_line = String::new();
-    io::stdin().read_line(&mut input_line).unwrap();
-    input_line
+    let num_bytes = input.read_line(&mut input_line).map_err(|error| format!("Unable to read input: {}", error))?;
+    if num_bytes == 0 {
+        return Err("Unexpected end of input".to_string());
+    }
+    Ok(input_line)
 }
 
-fn split_line(input_line: &str) -> Vec<&str> {
-    input_line.split(" ").collect::<Vec<&str>>()
+fn split_line(input_line: &str, num_inputs: usize) -> Result<Vec<&str>, String> {
+    let inputs = input_line.split_whitespace().collect::<Vec<&str>>();
+    if inputs.len() < num_inputs {
+        return Err(format!("Expected {} inputs: {:?}", num_inputs, input_line.trim()));
+    }
+    Ok(inputs)
 }
 
-fn parse_v2(x: &str, y: &str) -> V2 {
-    V2 {
+fn parse_v2(x: &str, y: &str) -> Result<V2, String> {
+    let pos = V2 {
         x: parse_input!(x, f32),
         y: parse_input!(y, f32),
+    };
+    if !pos.x.is_finite() || !pos.y.is_finite() {
+        return Err(format!("Expected finite coordinates: {} {}", x, y));
     }
+    Ok(pos)
 }
 
-fn parse_human(input_line: &str) -> Human {
-    let inputs = split_line(input_line);
-    Human {
+fn parse_human(input_line: &str) -> Result<Human, String> {
+    let inputs = split_line(input_line, 3)?;
+    Ok(Human {
         id: parse_input!(inputs[0], i32),
-        pos: parse_v2(inputs[1], inputs[2]),
-    }
+        pos: parse_v2(inputs[1], inputs[2])?,
+    })
 }
 
-fn parse_zombie(input_line: &str) -> Zombie {
-    let inputs = split_line(input_line);
-    Zombie {
+fn parse_zombie(input_line: &str) -> Result<Zombie, String> {
+    let inputs = split_line(input_line, 5)?;
+    Ok(Zombie {
         id: parse_input!(inputs[0], i32),
-        pos: parse_v2(inputs[1], inputs[2]),
-        next: parse_v2(inputs[3], inputs[4]),
+        pos: parse_v2(inputs[1], inputs[2])?,
+        next: parse_v2(inputs[3], inputs[4])?,
+    })
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Strategy, String> {
+        let s = s.trim();
+        if !s.starts_with('[') { return Err(format!("Strategy must start with [id]: {}", s)); }
+        let end = s.find(']').ok_or(format!("Unterminated strategy id: {}", s))?;
+
+        let id = parse_number::<i32>(&s[1..end])?;
+        let milestones = s[(end+1)..].split_whitespace().map(|token| token.parse::<Milestone>()).collect::<Result<Vec<Milestone>, String>>()?;
+        Ok(Strategy { id, milestones })
+    }
+}
+
+impl FromStr for Milestone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Milestone, String> {
+        if let Some(rest) = s.strip_prefix("wz") {
+            let (count, radius) = rest.split_once('@').ok_or(format!("Expected wz<count>@<radius>: {}", s))?;
+            Ok(Milestone::WaitUntilZombiesWithin { count: parse_number(count)?, radius: parse_number(radius)? })
+        } else if let Some(rest) = s.strip_prefix('w') {
+            Ok(Milestone::Wait { ticks: parse_number(rest)? })
+        } else if let Some(rest) = s.strip_prefix('z') {
+            Ok(Milestone::KillZombie { zombie_id: parse_number(rest)? })
+        } else if let Some(rest) = s.strip_prefix('i') {
+            Ok(Milestone::InterceptZombie { zombie_id: parse_number(rest)? })
+        } else if let Some(rest) = s.strip_prefix('c') {
+            Ok(Milestone::ComboAt { zombie_ids: parse_ids(rest)? })
+        } else if let Some(rest) = s.strip_prefix('h') {
+            let sign = if rest.starts_with('-') { 1 } else { 0 };
+            let end = rest[sign..].find(|c: char| !c.is_ascii_digit()).map_or(rest.len(), |i| i + sign);
+            Ok(Milestone::ProtectHuman { human_id: parse_number(&rest[..end])?, until: rest[end..].parse::<GuardCondition>()? })
+        } else if let Some(rest) = s.strip_prefix('@') {
+            let start = rest.find('(').ok_or(format!("Expected @<anchor>(<x>,<y>): {}", s))?;
+            Ok(Milestone::MoveNear { anchor: rest[..start].parse::<Anchor>()?, offset: parse_point(&rest[start..])? })
+        } else if s.starts_with('(') {
+            Ok(Milestone::MoveTo { target: parse_point(s)? })
+        } else {
+            Err(format!("Unknown milestone: {}", s))
+        }
     }
-}
\ No newline at end of file
+}
+
+impl FromStr for Anchor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Anchor, String> {
+        if let Some(rest) = s.strip_prefix('h') {
+            Ok(Anchor::Human(parse_number(rest)?))
+        } else if let Some(rest) = s.strip_prefix('z') {
+            Ok(Anchor::Zombie(parse_number(rest)?))
+        } else if let Some(rest) = s.strip_prefix('c') {
+            Ok(Anchor::Centroid(parse_ids(rest)?))
+        } else {
+            Err(format!("Unknown anchor: {}", s))
+        }
+    }
+}
+
+impl FromStr for GuardCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<GuardCondition, String> {
+        if s.is_empty() {
+            Ok(GuardCondition::ThreatCleared)
+        } else if let Some(rest) = s.strip_prefix('t') {
+            Ok(GuardCondition::Ticks(parse_number(rest)?))
+        } else if let Some(rest) = s.strip_prefix('n') {
+            Ok(GuardCondition::ZombiesNear(parse_number(rest)?))
+        } else {
+            Err(format!("Unknown guard condition: {}", s))
+        }
+    }
+}
+
+fn parse_number<T: FromStr>(s: &str) -> Result<T, String> {
+    s.trim().parse::<T>().map_err(|_| format!("Invalid number: {}", s))
+}
+
+fn parse_ids(s: &str) -> Result<Vec<i32>, String> {
+    s.split('+').map(parse_number::<i32>).collect::<Result<Vec<i32>, String>>()
+}
+
+fn parse_point(s: &str) -> Result<V2, String> {
+    let inner = s.strip_prefix('(').and_then(|rest| rest.strip_suffix(')')).ok_or(format!("Expected (<x>,<y>): {}", s))?;
+    let (x, y) = inner.split_once(',').ok_or(format!("Expected (<x>,<y>): {}", s))?;
+    Ok(V2 { x: parse_number(x)?, y: parse_number(y)? })
+}
@@ -0,0 +1,124 @@
+pub use super::model::*;
+
+use std::fs::File;
+use std::io::BufReader;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use super::bots;
+use super::bots::{Bot, ScriptedBot};
+use super::bounds;
+use super::config::AgentConfig;
+use super::evaluation;
+use super::parser;
+use super::simulator;
+use super::tracker::ScoreTracker;
+
+const MAX_GAME_TICKS: i32 = 500;
+
+pub struct GameResult {
+    pub score: f32,
+    pub upper_bound: f32,
+    pub tick: i32,
+    pub num_humans: usize,
+    pub num_zombies: usize,
+    pub lost_tick: Option<i32>, // When the game was first detected as unwinnable
+}
+
+pub fn load_scenario(path: &str) -> World {
+    let file = File::open(path).unwrap_or_else(|_| panic!("Unable to open scenario {}", path));
+    parser::read_world_from(&mut BufReader::new(file), 0).unwrap_or_else(|error| panic!("Invalid scenario {}: {}", path, error))
+}
+
+pub fn play(initial: &World, bot: &mut dyn Bot) -> (GameResult, Vec<Event>) {
+    let mut world = initial.clone();
+    let mut events = Vec::<Event>::new();
+    let mut tracker = ScoreTracker::new();
+    tracker.observe(&world);
+    bot.start(&world);
+
+    let mut lost_tick: Option<i32> = None;
+    while world.tick < MAX_GAME_TICKS {
+        if lost_tick.is_none() && bounds::is_lost(&world) {
+            lost_tick = Some(world.tick);
+        }
+
+        let action = bot.act(&world);
+        bot.log(&world);
+
+        let tick_events = simulator::next(&mut world, &action);
+        let is_finished = tick_events.iter().any(|event| event.is_ending());
+        events.extend(tick_events);
+
+        let gain = tracker.observe(&world);
+        eprintln!("Score: {} (+{})", tracker.score, gain);
+
+        if is_finished { break; }
+    }
+
+    // Both ways of counting must agree, otherwise the simulator events and the observed kills disagree
+    let score = tracker.final_score(&world);
+    let predicted = evaluation::official_score(&events);
+    if score != predicted {
+        eprintln!("Mispredicted final score: {} -> {}", predicted, score);
+    }
+    bot.end(&world, score);
+
+    let result = GameResult {
+        score,
+        upper_bound: bounds::upper_bound_score(initial),
+        tick: world.tick,
+        num_humans: world.humans.len(),
+        num_zombies: world.zombies.len(),
+        lost_tick,
+    };
+    (result, events)
+}
+
+pub fn replay(initial: &World, strategy: &Strategy) -> (GameResult, Vec<Event>) {
+    play(initial, &mut ScriptedBot::new(strategy.clone()))
+}
+
+pub fn run_replay(path: &str, strategies: &[String]) {
+    let initial = load_scenario(path);
+    for text in strategies.iter() {
+        let strategy = match text.parse::<Strategy>() {
+            Ok(strategy) => strategy,
+            Err(error) => {
+                println!("{}: {}", text, error);
+                continue;
+            },
+        };
+
+        let (result, events) = replay(&initial, &strategy);
+        println!(
+            "{}: score {} of upper bound {} (gap {:.1}%) at tick {} (h={}, z={})",
+            strategy, result.score, result.upper_bound, bounds::optimality_gap(result.score, result.upper_bound),
+            result.tick, result.num_humans, result.num_zombies);
+        for event in events.iter() {
+            println!(" {}", event);
+        }
+    }
+}
+
+pub fn run(paths: &[String], bot_name: &str, config: &AgentConfig, rng: &mut StdRng) {
+    let mut total_score = 0.0;
+    let mut total_upper_bound = 0.0;
+
+    for path in paths.iter() {
+        let initial = load_scenario(path);
+        let mut bot = bots::create(bot_name, config, StdRng::seed_from_u64(rng.gen())).unwrap_or_else(|error| panic!("{}", error));
+        let (result, _) = play(&initial, bot.as_mut());
+        println!(
+            "{}: score {} of upper bound {} (gap {:.1}%) at tick {} (h={}, z={})",
+            path, result.score, result.upper_bound, bounds::optimality_gap(result.score, result.upper_bound),
+            result.tick, result.num_humans, result.num_zombies);
+        if let Some(lost_tick) = result.lost_tick {
+            println!(" guaranteed loss detected at tick {}", lost_tick);
+        }
+
+        total_score += result.score;
+        total_upper_bound += result.upper_bound;
+    }
+
+    println!("Total: score {} of upper bound {} (gap {:.1}%)", total_score, total_upper_bound, bounds::optimality_gap(total_score, total_upper_bound));
+}
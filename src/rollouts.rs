@@ -1,5 +1,7 @@
 pub use super::model::*;
 
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use super::evaluation::{ScoreAccumulator, ScoreParams};
 use super::simulator;
 
@@ -11,6 +13,21 @@ pub struct Rollout {
     pub events: Vec<Event>,
     pub ending: WorldState,
     pub scores: Vec<f32>,
+    pub action_fingerprint: u64, // of the emitted action sequence
+    pub effective: Vec<bool>, // per milestone, whether it affected the rollout
+}
+
+impl Rollout {
+    pub fn simplified(&self) -> Strategy {
+        // Plays out identically, without the milestones that completed before emitting any action
+        let mut strategy = Strategy::new(self.strategy.id);
+        strategy.milestones = self.strategy.milestones.iter()
+            .zip(self.effective.iter())
+            .filter(|(_, effective)| **effective)
+            .map(|(milestone, _)| milestone.clone())
+            .collect::<Vec<Milestone>>();
+        strategy
+    }
 }
 
 #[derive(Clone)]
@@ -20,6 +37,12 @@ pub struct WorldState {
     pub num_humans: usize,
 }
 
+impl Default for WorldState {
+    fn default() -> WorldState {
+        WorldState::new()
+    }
+}
+
 impl WorldState {
     pub fn new() -> WorldState {
         WorldState { tick: -1, num_zombies: 0, num_humans: 0 }
@@ -33,11 +56,13 @@ impl WorldState {
 struct ActionEmitter<'a> {
     strategy: &'a Strategy,
     current_index: usize,
+    current_elapsed: i32,
+    emitted: Vec<bool>,
 }
 
 impl ActionEmitter<'_> {
     pub fn new<'a>(strategy: &'a Strategy) -> ActionEmitter<'a> {
-        ActionEmitter { strategy, current_index: 0 }
+        ActionEmitter { strategy, current_index: 0, current_elapsed: 0, emitted: vec![false; strategy.milestones.len()] }
     }
 
     pub fn next(&mut self, world: &World) -> Action {
@@ -45,25 +70,46 @@ impl ActionEmitter<'_> {
 
         while self.current_index < self.strategy.milestones.len() {
             let milestone = &self.strategy.milestones[self.current_index];
-            action = milestone.to_action(world);
+            action = milestone.to_action(world, self.current_elapsed);
 
             match action {
-                Some(_) => break, // Found a valid action, return it and don't advance to next milestone
-                None => { self.current_index += 1 }, // Milestone complete, consume this milestone
+                Some(_) => { // Found a valid action, return it and don't advance to next milestone
+                    self.emitted[self.current_index] = true;
+                    break;
+                },
+                None => { // Milestone complete, consume this milestone
+                    self.current_index += 1;
+                    self.current_elapsed = 0;
+                },
             }
         }
+        self.current_elapsed += 1;
 
         action.unwrap_or(Action { target: world.ash.pos })
     }
+
+    pub fn remaining(&self) -> Strategy {
+        let mut strategy = self.strategy.clone();
+        if self.current_index < strategy.milestones.len() {
+            strategy.milestones[self.current_index] = strategy.milestones[self.current_index].after(self.current_elapsed);
+        }
+        strategy
+    }
+
+    pub fn effective(&self, is_finished: bool) -> Vec<bool> {
+        // Milestones not yet reached may still act, unless the game is already over
+        (0..self.emitted.len()).map(|i| self.emitted[i] || (!is_finished && i >= self.current_index)).collect::<Vec<bool>>()
+    }
 }
 
 
-pub fn rollout(strategy: Strategy, initial: &World, score_params: &Vec<ScoreParams>) -> Rollout {
+pub fn rollout(strategy: Strategy, initial: &World, score_params: &[ScoreParams]) -> Rollout {
     let mut world = initial.clone();
     let mut events = Vec::<Event>::new();
 
     let mut score_accumulators = score_params.iter().map(|params| ScoreAccumulator::new(&world, params)).collect::<Vec<_>>();
     let mut action_emitter = ActionEmitter::new(&strategy);
+    let mut action_hasher = DefaultHasher::new();
 
     for score_accumulator in score_accumulators.iter_mut() {
         score_accumulator.evaluate_strategy(&strategy);
@@ -71,6 +117,7 @@ pub fn rollout(strategy: Strategy, initial: &World, score_params: &Vec<ScorePara
 
     for _ in 0..MAX_ROLLOUT_TICKS {
         let action = action_emitter.next(&world);
+        action.target.hash(&mut action_hasher);
         let tick_events = simulator::next(&mut world, &action);
 
         for score_accumulator in score_accumulators.iter_mut() {
@@ -78,20 +125,37 @@ pub fn rollout(strategy: Strategy, initial: &World, score_params: &Vec<ScorePara
         }
 
         let is_finished = tick_events.iter().any(|event| event.is_ending());
-        events.extend(tick_events.into_iter());
+        events.extend(tick_events);
 
         if is_finished { break; }
     }
 
+    let is_finished = events.iter().any(|event| event.is_ending());
+    if !is_finished {
+        for score_accumulator in score_accumulators.iter_mut() {
+            score_accumulator.evaluate_ending(&world);
+        }
+    }
+
+    let effective = action_emitter.effective(is_finished);
     Rollout {
         strategy,
         events,
         ending: WorldState::from(&world),
         scores: score_accumulators.iter().map(|x| x.total_score).collect::<Vec<f32>>(),
+        action_fingerprint: action_hasher.finish(),
+        effective,
     }
 }
 
 pub fn strategy_to_action(strategy: &Strategy, world: &World) -> Action {
     let mut action_emitter = ActionEmitter::new(strategy);
     action_emitter.next(world)
+}
+
+pub fn advance(strategy: &Strategy, world: &World) -> Strategy {
+    // Carry a strategy over to the next tick, accounting for the time already spent on its current milestone
+    let mut action_emitter = ActionEmitter::new(strategy);
+    action_emitter.next(world);
+    action_emitter.remaining()
 }
\ No newline at end of file
@@ -1,17 +1,24 @@
 pub use super::model::*;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 pub struct FibonacciSequence {
     previous: (i32, i32),
 }
 
+impl Default for FibonacciSequence {
+    fn default() -> FibonacciSequence {
+        FibonacciSequence::new()
+    }
+}
+
 impl FibonacciSequence {
     pub fn new() -> FibonacciSequence {
         FibonacciSequence { previous: (0, 1) }
     }
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> i32 {
         let (a, b) = self.previous;
-        let result = a + b;
+        let result = a.saturating_add(b); // A combo of 46+ zombies would overflow
         self.previous = (b, result);
         result
     }
@@ -23,7 +30,7 @@ pub fn next(world: &mut World, action: &Action) -> Vec<Event> {
     let mut events = Vec::<Event>::new();
     if !is_over(world) {
         move_zombies(world);
-        move_ash(world, &action);
+        move_ash(world, action);
         destroy_zombies(world, &mut events);
         destroy_humans(world, &mut events);
         update_zombie_targets(world);
@@ -39,25 +46,43 @@ fn move_zombies(world: &mut World) {
     }
 }
 
-fn update_zombie_targets(world: &mut World) {
+pub fn update_zombie_targets(world: &mut World) {
     let humans = &world.humans;
     let zombies = &mut world.zombies;
     for zombie in zombies.values_mut() {
-        let mut target = world.ash.pos;
-        let mut target_distance = zombie.pos.distance_to(target);
-
-        for human in humans.values() {
-            let distance = zombie.pos.distance_to(human.pos);
-            if distance < target_distance {
-                target_distance = distance;
-                target = human.pos;
-            }
-        }
-
+        let target = find_zombie_target(zombie.pos, &world.ash, humans);
         zombie.next = zombie.pos.towards(target, constants::MAX_ZOMBIE_STEP).floor();
     }
 }
 
+pub fn project_zombie(zombie: &Zombie, world: &World, num_ticks: usize) -> Vec<V2> {
+    // Positions after each of the next ticks, assuming the zombie keeps its current target
+    let target = find_zombie_target(zombie.pos, &world.ash, &world.humans);
+
+    let mut path = Vec::<V2>::with_capacity(num_ticks);
+    let mut pos = zombie.next;
+    for _ in 0..num_ticks {
+        path.push(pos);
+        pos = pos.towards(target, constants::MAX_ZOMBIE_STEP).floor();
+    }
+    path
+}
+
+pub fn find_zombie_target(pos: V2, ash: &Ash, humans: &BTreeMap<i32, Human>) -> V2 {
+    let mut target = ash.pos;
+    let mut target_distance = pos.distance_to(target);
+
+    for human in humans.values() {
+        let distance = pos.distance_to(human.pos);
+        if distance < target_distance {
+            target_distance = distance;
+            target = human.pos;
+        }
+    }
+
+    target
+}
+
 fn move_ash(world: &mut World, action: &Action) {
     world.ash.pos = world.ash.pos.towards(action.target, constants::MAX_ASH_STEP).floor();
 }
@@ -93,8 +118,8 @@ pub fn calculate_zombie_kill_score(num_humans: i32) -> f32 {
 }
 
 fn destroy_humans(world: &mut World, events: &mut Vec<Event>) {
-    if world.zombies.len() == 0 { return; } // Nothing to kill the humans
-    if world.humans.len() == 0 { return; } // Game was already over before this turn started
+    if world.zombies.is_empty() { return; } // Nothing to kill the humans
+    if world.humans.is_empty() { return; } // Game was already over before this turn started
 
     let max_distance_squared = constants::MAX_ZOMBIE_KILL_RANGE.powf(2.0);
     let mut human_ids_to_delete = HashSet::<i32>::new();
@@ -112,13 +137,13 @@ fn destroy_humans(world: &mut World, events: &mut Vec<Event>) {
 }
 
 fn is_over(world: &World) -> bool {
-    world.humans.len() == 0 || world.zombies.len() == 0
+    world.humans.is_empty() || world.zombies.is_empty()
 }
 
 fn emit_ending(world: &mut World, events: &mut Vec<Event>) {
-    if world.humans.len() == 0 {
+    if world.humans.is_empty() {
         events.push(Event::Lost { tick: world.tick, num_zombies: world.zombies.len() });
-    } else if world.zombies.len() == 0 {
+    } else if world.zombies.is_empty() {
         events.push(Event::Won{ tick: world.tick, num_humans: world.humans.len() });
     }
 }
\ No newline at end of file
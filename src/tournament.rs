@@ -0,0 +1,198 @@
+pub use super::model::*;
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use super::bots::{Bot, PoolBot};
+use super::config::AgentConfig;
+use super::generator;
+use super::referee;
+use super::referee::GameResult;
+
+const EXACT_PERMUTATION_LIMIT: usize = 16; // Enumerate every sign flip up to 2^16, sample beyond that
+const NUM_SAMPLED_PERMUTATIONS: usize = 20000;
+
+// Either an agent configuration played in-process or another agent binary speaking the CodinGame protocol
+pub enum Contender {
+    Config(AgentConfig),
+    Binary(String),
+}
+
+impl Contender {
+    pub fn parse(spec: &str, config: &AgentConfig) -> Result<Contender, String> {
+        // "default" is the current configuration, *.cfg a configuration file, anything else an executable and its arguments
+        if spec == "default" {
+            Ok(Contender::Config(config.clone()))
+        } else if spec.ends_with(".cfg") {
+            Ok(Contender::Config(AgentConfig::load(spec)?))
+        } else {
+            Ok(Contender::Binary(spec.to_string()))
+        }
+    }
+
+    fn create(&self, seed: u64) -> Box<dyn Bot> {
+        match self {
+            Contender::Config(config) => {
+                // The scenario library is the opening book, which would decide every game at tick 0 for both contenders
+                let mut config = config.clone();
+                config.use_opening_book = false;
+                Box::new(PoolBot::new(config, StdRng::seed_from_u64(seed)))
+            },
+            Contender::Binary(command) => Box::new(ProcessBot::spawn(command)),
+        }
+    }
+}
+
+pub struct ProcessBot {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ProcessBot {
+    pub fn spawn(command: &str) -> ProcessBot {
+        // The command may include arguments, separated by whitespace
+        let mut words = command.split_whitespace();
+        let mut child = Command::new(words.next().unwrap_or_default())
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap_or_else(|error| panic!("Unable to start {}: {}", command, error));
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        ProcessBot { child, stdin, stdout }
+    }
+}
+
+impl Bot for ProcessBot {
+    fn act(&mut self, world: &World) -> Action {
+        // Every tick's input has the same layout as a scenario file
+        self.stdin.write_all(generator::scenario_text(world).as_bytes()).unwrap();
+        self.stdin.flush().unwrap();
+
+        let mut line = String::new();
+        self.stdout.read_line(&mut line).unwrap();
+        let inputs = line.split_whitespace().collect::<Vec<&str>>();
+        match (inputs.first().and_then(|x| x.parse::<f32>().ok()), inputs.get(1).and_then(|y| y.parse::<f32>().ok())) {
+            (Some(x), Some(y)) => Action { target: V2 { x, y } },
+            _ => {
+                eprintln!("Invalid output from bot: {}", line.trim());
+                Action { target: world.ash.pos }
+            },
+        }
+    }
+
+    fn end(&mut self, _world: &World, _score: f32) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+struct Pairing {
+    scenario: String,
+    seed: u64,
+    a: GameResult,
+    b: GameResult,
+}
+
+pub fn run(a: &Contender, b: &Contender, num_seeds: u64, output: &str, paths: &[String], rng: &mut StdRng) {
+    let mut pairings = Vec::<Pairing>::new();
+    for path in paths.iter() {
+        let initial = referee::load_scenario(path);
+        for seed in 0..num_seeds {
+            // Both contenders get the same scenario and seed so their results can be compared pairwise
+            let (result_a, _) = referee::play(&initial, a.create(seed).as_mut());
+            let (result_b, _) = referee::play(&initial, b.create(seed).as_mut());
+            eprintln!("{} seed {}: A {} vs B {}", path, seed, result_a.score, result_b.score);
+            pairings.push(Pairing { scenario: path.clone(), seed, a: result_a, b: result_b });
+        }
+    }
+
+    let markdown = summarize(&pairings, rng);
+    print!("{}", markdown);
+
+    fs::write(format!("{}.md", output), &markdown).unwrap_or_else(|error| panic!("Unable to write {}.md: {}", output, error));
+    fs::write(format!("{}.csv", output), to_csv(&pairings)).unwrap_or_else(|error| panic!("Unable to write {}.csv: {}", output, error));
+}
+
+fn summarize(pairings: &[Pairing], rng: &mut StdRng) -> String {
+    let scores_a = pairings.iter().map(|pairing| pairing.a.score).collect::<Vec<f32>>();
+    let scores_b = pairings.iter().map(|pairing| pairing.b.score).collect::<Vec<f32>>();
+    let humans_a = pairings.iter().map(|pairing| pairing.a.num_humans as f32).collect::<Vec<f32>>();
+    let humans_b = pairings.iter().map(|pairing| pairing.b.num_humans as f32).collect::<Vec<f32>>();
+    let differences = pairings.iter().map(|pairing| pairing.a.score - pairing.b.score).collect::<Vec<f32>>();
+
+    let wins_a = differences.iter().filter(|difference| **difference > 0.0).count();
+    let wins_b = differences.iter().filter(|difference| **difference < 0.0).count();
+    let num_games = pairings.len().max(1) as f32;
+
+    let mut text = format!("# Tournament: {} paired games\n\n", pairings.len());
+    text.push_str("| | A | B |\n|---|---|---|\n");
+    text.push_str(&format!("| Mean score | {:.1} | {:.1} |\n", mean(&scores_a), mean(&scores_b)));
+    text.push_str(&format!("| Median score | {:.1} | {:.1} |\n", median(&scores_a), median(&scores_b)));
+    text.push_str(&format!("| Win rate | {:.1}% | {:.1}% |\n", 100.0 * wins_a as f32 / num_games, 100.0 * wins_b as f32 / num_games));
+    text.push_str(&format!("| Mean humans saved | {:.2} | {:.2} |\n", mean(&humans_a), mean(&humans_b)));
+    text.push_str(&format!(
+        "\nMean difference (A - B) {:.1}, paired permutation test p = {:.4} (two-sided)\n",
+        mean(&differences), paired_permutation_test(&differences, rng)));
+    text
+}
+
+fn to_csv(pairings: &[Pairing]) -> String {
+    let mut text = String::from("scenario,seed,score_a,score_b,humans_a,humans_b,tick_a,tick_b\n");
+    for pairing in pairings.iter() {
+        text.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            pairing.scenario, pairing.seed, pairing.a.score, pairing.b.score,
+            pairing.a.num_humans, pairing.b.num_humans, pairing.a.tick, pairing.b.tick));
+    }
+    text
+}
+
+fn mean(values: &[f32]) -> f32 {
+    if values.is_empty() { return 0.0; }
+    values.iter().sum::<f32>() / values.len() as f32
+}
+
+fn median(values: &[f32]) -> f32 {
+    if values.is_empty() { return 0.0; }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    }
+}
+
+pub fn paired_permutation_test(differences: &[f32], rng: &mut StdRng) -> f32 {
+    // Under the null hypothesis each difference is equally likely to have either sign,
+    // so count how often a random assignment of signs is at least as extreme as the observed one
+    let differences = differences.iter().filter(|difference| **difference != 0.0).cloned().collect::<Vec<f32>>();
+    if differences.is_empty() { return 1.0; }
+
+    let observed = differences.iter().sum::<f32>().abs();
+    let is_extreme = |signs: &dyn Fn(usize) -> bool| {
+        let total = differences.iter().enumerate().map(|(i, difference)| if signs(i) { *difference } else { -difference }).sum::<f32>();
+        total.abs() >= observed - 1e-3
+    };
+
+    if differences.len() <= EXACT_PERMUTATION_LIMIT {
+        let num_permutations = 1usize << differences.len();
+        let num_extreme = (0..num_permutations).filter(|mask| is_extreme(&|i| mask & (1 << i) != 0)).count();
+        num_extreme as f32 / num_permutations as f32
+    } else {
+        let num_extreme = (0..NUM_SAMPLED_PERMUTATIONS).filter(|_| {
+            let signs = (0..differences.len()).map(|_| rng.gen::<bool>()).collect::<Vec<bool>>();
+            is_extreme(&|i| signs[i])
+        }).count();
+        (num_extreme + 1) as f32 / (NUM_SAMPLED_PERMUTATIONS + 1) as f32
+    }
+}
@@ -0,0 +1,51 @@
+pub use super::model::*;
+
+use super::simulator;
+use super::simulator::FibonacciSequence;
+
+// Follows the official score from the worlds the referee sends us, independently of our own predictions
+pub struct ScoreTracker {
+    pub score: f32,
+    previous: Option<World>,
+}
+
+impl Default for ScoreTracker {
+    fn default() -> ScoreTracker {
+        ScoreTracker::new()
+    }
+}
+
+impl ScoreTracker {
+    pub fn new() -> ScoreTracker {
+        ScoreTracker { score: 0.0, previous: None }
+    }
+
+    pub fn observe(&mut self, world: &World) -> f32 {
+        let gain = match &self.previous {
+            Some(previous) => ScoreTracker::kill_score(previous, world),
+            None => 0.0,
+        };
+
+        self.score += gain;
+        self.previous = Some(world.clone());
+        gain
+    }
+
+    pub fn final_score(&self, world: &World) -> f32 {
+        // Losing every human forfeits everything banked so far
+        if world.humans.is_empty() { 0.0 } else { self.score }
+    }
+
+    fn kill_score(previous: &World, current: &World) -> f32 {
+        // Zombies are shot before humans are eaten, so the kill score uses the humans alive at the start of the tick
+        let num_killed = previous.zombies.keys().filter(|zombie_id| !current.zombies.contains_key(zombie_id)).count();
+        let score = simulator::calculate_zombie_kill_score(previous.humans.len() as i32);
+
+        let mut multiplier_sequence = FibonacciSequence::new();
+        let mut gain = 0.0;
+        for _ in 0..num_killed {
+            gain += score * multiplier_sequence.next() as f32;
+        }
+        gain
+    }
+}
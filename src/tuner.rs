@@ -0,0 +1,112 @@
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use super::bots::PoolBot;
+use super::config::AgentConfig;
+use super::referee;
+
+const TUNER_ROLLOUTS_PER_TICK: usize = 200;
+const TUNER_SEEDS: u64 = 3;
+
+const PERTURB_PROBABILITY: f32 = 0.3;
+const PERTURB_SCALE: f32 = 0.25;
+
+// Parameters the tuner may change, with the range each is allowed to take
+const TUNABLE: &[(&str, f32, f32)] = &[
+    ("mutate_proportion", 0.0, 1.0),
+    ("intercept_from_scratch_proportion", 0.0, 1.0),
+    ("replace_move_proportion", 0.0, 1.0),
+    ("bump_move_proportion", 0.0, 1.0),
+    ("anchored_move_proportion", 0.0, 1.0),
+    ("bump_offset_proportion", 0.0, 1.0),
+    ("attack_zombie_proportion", 0.0, 1.0),
+    ("intercept_zombie_proportion", 0.0, 1.0),
+    ("switch_attack_proportion", 0.0, 1.0),
+    ("combo_proportion", 0.0, 1.0),
+    ("protect_human_proportion", 0.0, 1.0),
+    ("tune_guard_proportion", 0.0, 1.0),
+    ("drop_proportion", 0.0, 1.0),
+    ("wait_proportion", 0.0, 1.0),
+    ("adjust_wait_proportion", 0.0, 1.0),
+    ("bubble_proportion", 0.0, 1.0),
+    ("swap_proportion", 0.0, 1.0),
+    ("displace_proportion", 0.0, 1.0),
+    ("loss_points", -100000.0, 0.0),
+    ("points_per_zombie", -10000.0, 0.0),
+    ("bonus_points_per_human", -100000.0, 0.0),
+    ("score_sheet_spread", 0.0, 1.0),
+    ("max_urgency_weight", 0.0, 0.2),
+    ("max_terminal_weight", 0.0, 1.0),
+    ("reseed_mutate_proportion", 0.0, 1.0),
+];
+
+// Integer parameters, which move by at least one step when perturbed
+const INTEGER_TUNABLE: &[(&str, i32, i32)] = &[
+    ("max_moves_from_scratch", 0, 4),
+    ("score_sheet_size", 1, 12),
+];
+
+pub fn run(iterations: i32, paths: &[String], config: &AgentConfig, rng: &mut StdRng) {
+    let mut best = config.clone();
+    if best.max_rollouts_per_tick == 0 {
+        best.max_rollouts_per_tick = TUNER_ROLLOUTS_PER_TICK;
+    }
+    let mut best_score = evaluate(&best, paths);
+    println!("# Initial: {}", best_score);
+
+    for iteration in 0..iterations {
+        let candidate = perturb(&best, rng);
+        let score = evaluate(&candidate, paths);
+        println!("# Iteration {}: {} (best {})", iteration, score, best_score);
+
+        if score > best_score {
+            best = candidate;
+            best_score = score;
+        }
+    }
+
+    println!("# Best configuration, mean score {}", best_score);
+    print!("{}", best);
+}
+
+pub fn evaluate(config: &AgentConfig, paths: &[String]) -> f32 {
+    // Same seeds for every configuration so that they are compared on equal terms.
+    // The scenarios are in the opening book, which would otherwise decide every game at tick 0
+    let mut config = config.clone();
+    config.use_opening_book = false;
+
+    let mut total_score = 0.0;
+    let mut num_games = 0;
+    for path in paths.iter() {
+        let initial = referee::load_scenario(path);
+        for seed in 0..TUNER_SEEDS {
+            let mut bot = PoolBot::new(config.clone(), StdRng::seed_from_u64(seed));
+            total_score += referee::play(&initial, &mut bot).0.score;
+            num_games += 1;
+        }
+    }
+    total_score / (num_games.max(1) as f32)
+}
+
+fn perturb(config: &AgentConfig, rng: &mut StdRng) -> AgentConfig {
+    let mut candidate = config.clone();
+    for (key, min_value, max_value) in TUNABLE.iter() {
+        if rng.gen::<f32>() >= PERTURB_PROBABILITY { continue; }
+
+        let value = candidate.get(key).and_then(|value| value.parse::<f32>().ok()).unwrap();
+        let step = PERTURB_SCALE * (max_value - min_value) * rng.gen_range(-1.0..1.0f32);
+        let value = (value + step).max(*min_value).min(*max_value);
+        candidate.set(key, &value.to_string()).unwrap();
+    }
+
+    for (key, min_value, max_value) in INTEGER_TUNABLE.iter() {
+        if rng.gen::<f32>() >= PERTURB_PROBABILITY { continue; }
+
+        let value = candidate.get(key).and_then(|value| value.parse::<i32>().ok()).unwrap();
+        let max_step = ((PERTURB_SCALE * (max_value - min_value) as f32).round() as i32).max(1);
+        let step = rng.gen_range(1..(max_step + 1)) * if rng.gen::<bool>() { 1 } else { -1 };
+        let value = (value + step).max(*min_value).min(*max_value);
+        candidate.set(key, &value.to_string()).unwrap();
+    }
+    candidate
+}
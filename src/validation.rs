@@ -0,0 +1,67 @@
+use super::model::*;
+
+use std::collections::HashSet;
+
+impl Strategy {
+    // A strategy is normalized when it attacks each zombie at most once, only refers to living humans and zombies,
+    // and keeps its move targets on the map
+    pub fn validate(&self, world: &World) -> Result<(), String> {
+        let mut attacked = HashSet::<i32>::new();
+        for milestone in self.milestones.iter() {
+            if let Some(zombie_id) = milestone.attacks_zombie() {
+                if !attacked.insert(zombie_id) {
+                    return Err(format!("Zombie {} attacked more than once: {}", zombie_id, self));
+                }
+            }
+
+            if !refers_to_living(milestone, world) {
+                return Err(format!("{} refers to a dead human or zombie: {}", milestone, self));
+            }
+
+            if let Milestone::MoveTo { target } = milestone {
+                if target.clamp_to_map() != *target {
+                    return Err(format!("{} is off the map: {}", milestone, self));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn normalize(&mut self, world: &World) {
+        let mut attacked = HashSet::<i32>::new();
+        let milestones = std::mem::take(&mut self.milestones);
+        for milestone in milestones {
+            let milestone = match milestone {
+                Milestone::ComboAt { zombie_ids } => Milestone::ComboAt { zombie_ids: living_zombies(zombie_ids, world) },
+                Milestone::MoveNear { anchor: Anchor::Centroid(zombie_ids), offset } => Milestone::MoveNear { anchor: Anchor::Centroid(living_zombies(zombie_ids, world)), offset },
+                Milestone::MoveTo { target } => Milestone::MoveTo { target: target.clamp_to_map() },
+                other => other,
+            };
+
+            if let Some(zombie_id) = milestone.attacks_zombie() {
+                if !attacked.insert(zombie_id) { continue; } // Keep the first attack only
+            }
+            if !refers_to_living(&milestone, world) { continue; }
+
+            self.milestones.push(milestone);
+        }
+    }
+}
+
+fn refers_to_living(milestone: &Milestone, world: &World) -> bool {
+    match milestone {
+        Milestone::KillZombie { zombie_id } | Milestone::InterceptZombie { zombie_id } => world.zombies.contains_key(zombie_id),
+        Milestone::ComboAt { zombie_ids } => !zombie_ids.is_empty() && zombie_ids.iter().all(|id| world.zombies.contains_key(id)),
+        Milestone::ProtectHuman { human_id, .. } => world.humans.contains_key(human_id),
+        Milestone::MoveNear { anchor, .. } => match anchor {
+            Anchor::Human(human_id) => world.humans.contains_key(human_id),
+            Anchor::Zombie(zombie_id) => world.zombies.contains_key(zombie_id),
+            Anchor::Centroid(zombie_ids) => !zombie_ids.is_empty() && zombie_ids.iter().all(|id| world.zombies.contains_key(id)),
+        },
+        _ => true,
+    }
+}
+
+fn living_zombies(zombie_ids: Vec<i32>, world: &World) -> Vec<i32> {
+    zombie_ids.into_iter().filter(|id| world.zombies.contains_key(id)).collect::<Vec<i32>>()
+}
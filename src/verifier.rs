@@ -5,6 +5,13 @@ use super::model::*;
 pub struct Verifier {
     previous: World,
     predicted: World,
+    predicted_events: Vec<Event>,
+}
+
+impl Default for Verifier {
+    fn default() -> Verifier {
+        Verifier::new()
+    }
 }
 
 impl Verifier {
@@ -12,6 +19,7 @@ impl Verifier {
         Verifier {
             previous: World::new(),
             predicted: World::new(),
+            predicted_events: Vec::new(),
         }
     }
 
@@ -19,10 +27,32 @@ impl Verifier {
         self.previous = world.clone();
 
         let mut predicted = world.clone();
-        simulator::next(&mut predicted, action);
+        self.predicted_events = simulator::next(&mut predicted, action);
         self.predicted = predicted;
     }
 
+    pub fn predicted_ending(&self) -> Option<&Event> {
+        self.predicted_events.iter().find(|event| event.is_ending())
+    }
+
+    pub fn predicted_gain(&self) -> f32 {
+        self.predicted_events.iter().map(|event| match event {
+            Event::ZombieKilled { score, multiplier, .. } => score * multiplier,
+            _ => 0.0,
+        }).sum::<f32>()
+    }
+
+    pub fn log_score_error(&self, world: &World, observed: f32) {
+        if world.tick <= 0 { return; } // No predicting for first tick
+
+        const PRECISION: f32 = 0.5;
+
+        let predicted = self.predicted_gain();
+        if (predicted - observed).abs() > PRECISION {
+            eprintln!("Mispredicted score: +{} -> +{}", predicted, observed);
+        }
+    }
+
     pub fn log_prediction_error(&self, world: &World) {
         if world.tick <= 0 { return; } // No predicting for first tick
 
@@ -32,8 +62,8 @@ impl Verifier {
             let predicted = self.predicted.zombies.get(&initial.id);
             let current = world.zombies.get(&initial.id);
             Verifier::log_alive_equivalent(initial, predicted, current);
-            if predicted.is_some() && current.is_some() {
-                Verifier::log_position_equivalent(initial, predicted.unwrap(), current.unwrap());
+            if let (Some(predicted), Some(current)) = (predicted, current) {
+                Verifier::log_position_equivalent(initial, predicted, current);
             }
         }
 
@@ -41,8 +71,8 @@ impl Verifier {
             let predicted = self.predicted.humans.get(&initial.id);
             let current = world.humans.get(&initial.id);
             Verifier::log_alive_equivalent(initial, predicted, current);
-            if predicted.is_some() && current.is_some() {
-                Verifier::log_position_equivalent(initial, predicted.unwrap(), current.unwrap());
+            if let (Some(predicted), Some(current)) = (predicted, current) {
+                Verifier::log_position_equivalent(initial, predicted, current);
             }
         }
     }
@@ -0,0 +1,111 @@
+#![cfg(feature = "local")]
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use codevszombies::model::*;
+use codevszombies::{bots, bounds, generator, referee, simulator};
+use codevszombies::bots::{Bot, ScriptedBot};
+use codevszombies::config::AgentConfig;
+use codevszombies::generator::ScenarioParams;
+
+mod common;
+use common::gen_world;
+
+const NUM_WORLDS: u64 = 300;
+const NUM_POOL_WORLDS: u64 = 20;
+const POOL_ROLLOUTS_PER_TICK: usize = 20;
+const BOT_NAMES: &[&str] = &["pool", "nearest-zombie", "guard"];
+
+fn gen_worlds(assignments: &[(&str, &str)], count: u64) -> Vec<World> {
+    let mut params = ScenarioParams::new();
+    for (key, value) in assignments {
+        params.set(key, value).unwrap();
+    }
+    (0..count).map(|seed| generator::generate(&params, seed).unwrap()).collect()
+}
+
+fn worlds(count: u64) -> Vec<World> {
+    // Doomed humans are where a kill made before a human dies is worth more than one made after
+    let mut worlds = gen_worlds(&[("humans", "2"), ("zombies", "3"), ("doomed", "1.0")], count);
+    worlds.extend(gen_worlds(&[("doomed", "0.5"), ("ash", "far")], count));
+    worlds.extend((0..count).map(|seed| gen_world(&mut StdRng::seed_from_u64(seed))));
+    worlds
+}
+
+fn check_upper_bound(bot_name: &str, config: &AgentConfig, worlds: &[World]) {
+    for (i, initial) in worlds.iter().enumerate() {
+        let mut bot = bots::create(bot_name, config, StdRng::seed_from_u64(i as u64)).unwrap();
+        let (result, _) = referee::play(initial, bot.as_mut());
+        assert!(result.score <= bounds::upper_bound_score(initial), "{} scored {} above the upper bound {} in world {}", bot_name, result.score, result.upper_bound, i);
+    }
+}
+
+#[test]
+fn baselines_never_beat_upper_bound() {
+    let worlds = worlds(NUM_WORLDS);
+    check_upper_bound("nearest-zombie", &AgentConfig::new(), &worlds);
+    check_upper_bound("guard", &AgentConfig::new(), &worlds);
+}
+
+#[test]
+fn agent_never_beats_upper_bound() {
+    let mut config = AgentConfig::new();
+    config.max_rollouts_per_tick = POOL_ROLLOUTS_PER_TICK;
+    check_upper_bound("pool", &config, &worlds(NUM_POOL_WORLDS));
+}
+
+fn scripted_bots(world: &World) -> Vec<ScriptedBot> {
+    // Rush each zombie or guard each human, the obvious ways of saving someone
+    let mut milestones = world.zombies.keys().map(|zombie_id| Milestone::KillZombie { zombie_id: *zombie_id }).collect::<Vec<Milestone>>();
+    milestones.extend(world.humans.keys().map(|human_id| Milestone::ProtectHuman { human_id: *human_id, until: GuardCondition::ThreatCleared }));
+    milestones.into_iter().map(|milestone| {
+        let mut strategy = Strategy::new(0);
+        strategy.milestones.push(milestone);
+        ScriptedBot::new(strategy)
+    }).collect()
+}
+
+#[test]
+fn is_lost_only_when_every_bot_loses() {
+    let mut config = AgentConfig::new();
+    config.max_rollouts_per_tick = POOL_ROLLOUTS_PER_TICK;
+
+    let mut num_lost = 0;
+    for (i, initial) in worlds(NUM_WORLDS).iter().enumerate() {
+        // The agent is only cheap enough to play the worlds declared lost
+        let lost = bounds::is_lost(initial);
+        let bot_names = if lost { BOT_NAMES } else { &BOT_NAMES[1..] };
+        let mut players = bot_names.iter().map(|name| bots::create(name, &config, StdRng::seed_from_u64(i as u64)).unwrap()).collect::<Vec<Box<dyn Bot>>>();
+        if lost {
+            num_lost += 1;
+            players.extend(scripted_bots(initial).into_iter().map(|bot| Box::new(bot) as Box<dyn Bot>));
+        }
+
+        for bot in players.iter_mut() {
+            let (result, _) = referee::play(initial, bot.as_mut());
+            if lost || result.lost_tick.is_some() {
+                assert_eq!(result.num_humans, 0, "World {} was declared lost at tick {:?} but {} humans survived", i, result.lost_tick, result.num_humans);
+            }
+        }
+    }
+    assert!(num_lost > 0, "No lost worlds were generated");
+}
+
+#[test]
+fn is_lost_allows_for_floored_zombie_steps() {
+    // Flooring slows zombie 2 by a tick, just long enough for Ash to save human 1
+    let mut world = World::new();
+    world.ash.pos = V2 { x: 15578.0, y: 154.0 };
+    for (id, x, y) in [(0, 14023.0, 8982.0), (1, 10561.0, 8915.0)] {
+        world.humans.insert(id, Human { id, pos: V2 { x, y } });
+    }
+    for (id, x, y) in [(0, 14171.0, 8999.0), (1, 13671.0, 5088.0), (2, 7736.0, 7424.0)] {
+        world.zombies.insert(id, Zombie { id, pos: V2 { x, y }, next: V2 { x, y } });
+    }
+    simulator::update_zombie_targets(&mut world);
+
+    assert!(!bounds::is_lost(&world));
+    let mut bot = bots::create("nearest-zombie", &AgentConfig::new(), StdRng::seed_from_u64(0)).unwrap();
+    let (result, _) = referee::play(&world, bot.as_mut());
+    assert!(result.num_humans > 0);
+}
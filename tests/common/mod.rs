@@ -0,0 +1,39 @@
+// Random worlds shared by the property tests
+#![allow(dead_code)]
+
+use rand::Rng;
+use rand::rngs::StdRng;
+use codevszombies::model::*;
+use codevszombies::simulator;
+
+pub fn gen_point(rng: &mut StdRng) -> V2 {
+    V2 {
+        x: rng.gen_range(0..constants::MAP_WIDTH) as f32,
+        y: rng.gen_range(0..constants::MAP_HEIGHT) as f32,
+    }
+}
+
+pub fn gen_near(centre: V2, radius: f32, rng: &mut StdRng) -> V2 {
+    V2 {
+        x: centre.x + rng.gen_range(-radius..radius),
+        y: centre.y + rng.gen_range(-radius..radius),
+    }.clamp_to_map().floor()
+}
+
+pub fn gen_world(rng: &mut StdRng) -> World {
+    let mut world = World::new();
+    world.ash.pos = gen_point(rng);
+
+    for id in 0..rng.gen_range(1..10) {
+        world.humans.insert(id, Human { id, pos: gen_point(rng) });
+    }
+
+    // Some zombies start near Ash so that combos happen
+    for id in 0..rng.gen_range(1..20) {
+        let pos = if rng.gen::<f32>() < 0.3 { gen_near(world.ash.pos, 3000.0, rng) } else { gen_point(rng) };
+        world.zombies.insert(id, Zombie { id, pos, next: pos });
+    }
+
+    simulator::update_zombie_targets(&mut world);
+    world
+}
@@ -0,0 +1,128 @@
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use codevszombies::model::*;
+use codevszombies::{parser, simulator};
+use codevszombies::bots::{Bot, PoolBot};
+use codevszombies::config::AgentConfig;
+
+mod common;
+use common::gen_world;
+
+const NUM_INPUTS: u64 = 5000;
+const NUM_GAMES: u64 = 20;
+const MAX_TICKS: i32 = 30;
+const ROLLOUTS_PER_TICK: usize = 10;
+
+const VALID_INPUT: &str = "0 0\n2\n0 8250 4500\n1 100 100\n2\n0 8250 8999 8250 8599\n1 15999 0 15600 0\n";
+const INPUT_ALPHABET: &[u8] = b"0123456789 -.\n\r\teNa";
+const STRATEGY_ALPHABET: &str = "[]0123456789zichtnw@(),+-. é";
+
+fn mutate_input(input: &[u8], rng: &mut StdRng) -> Vec<u8> {
+    let mut bytes = input.to_vec();
+    for _ in 0..rng.gen_range(1..5) {
+        let index = rng.gen_range(0..(bytes.len() + 1));
+        match rng.gen_range(0..4) {
+            0 if index < bytes.len() => { bytes.remove(index); },
+            1 => bytes.insert(index, INPUT_ALPHABET[rng.gen_range(0..INPUT_ALPHABET.len())]),
+            2 => bytes.insert(index, rng.gen::<u8>()),
+            _ => bytes.truncate(index),
+        }
+    }
+    bytes
+}
+
+#[test]
+fn read_world_never_panics_on_arbitrary_bytes() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..NUM_INPUTS {
+        let bytes = (0..rng.gen_range(0..200)).map(|_| rng.gen::<u8>()).collect::<Vec<u8>>();
+        let _ = parser::read_world_from(&mut bytes.as_slice(), 0);
+    }
+}
+
+#[test]
+fn read_world_never_panics_on_corrupted_input() {
+    let mut rng = StdRng::seed_from_u64(0);
+    for _ in 0..NUM_INPUTS {
+        let bytes = mutate_input(VALID_INPUT.as_bytes(), &mut rng);
+        if let Ok(world) = parser::read_world_from(&mut bytes.as_slice(), 0) {
+            let finite = |pos: V2| pos.x.is_finite() && pos.y.is_finite();
+            assert!(finite(world.ash.pos));
+            assert!(world.humans.values().all(|human| finite(human.pos)));
+            assert!(world.zombies.values().all(|zombie| finite(zombie.pos) && finite(zombie.next)));
+        }
+    }
+    assert!(parser::read_world_from(&mut VALID_INPUT.as_bytes(), 0).is_ok());
+}
+
+#[test]
+fn strategy_parser_never_panics() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let alphabet = STRATEGY_ALPHABET.chars().collect::<Vec<char>>();
+    for _ in 0..NUM_INPUTS {
+        let text = (0..rng.gen_range(0..30)).map(|_| alphabet[rng.gen_range(0..alphabet.len())]).collect::<String>();
+        let _ = text.parse::<Strategy>();
+        let _ = format!("[0] {}", text).parse::<Strategy>();
+    }
+}
+
+fn edge_case_inputs() -> Vec<String> {
+    let mut inputs = vec![
+        "0 0\n0\n1\n0 100 100 500 100\n".to_string(), // No humans
+        "0 0\n1\n0 8000 4500\n0\n".to_string(), // No zombies
+        "0 0\n0\n0\n".to_string(), // Nothing at all
+        "0 0\n2\n0 1000 1000\n0 2000 2000\n2\n3 9000 9000 8600 8999\n3 5000 5000 4700 4700\n".to_string(), // Duplicated ids
+        "15999 8999\n2\n0 0 0\n1 15999 0\n2\n0 0 8999 0 8599\n1 15999 8999 15599 8999\n".to_string(), // Map edges
+        "4000 4000\n1\n0 4000 4000\n1\n0 4000 4000 4000 4000\n".to_string(), // Everything in the same place
+    ];
+
+    // The maximum number of zombies
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut input = "8000 4500\n5\n".to_string();
+    for id in 0..5 {
+        input.push_str(&format!("{} {} {}\n", id, rng.gen_range(0..16000), rng.gen_range(0..9000)));
+    }
+    input.push_str("99\n");
+    for id in 0..99 {
+        let (x, y) = (rng.gen_range(0..16000), rng.gen_range(0..9000));
+        input.push_str(&format!("{} {} {} {} {}\n", id, x, y, x, y));
+    }
+    inputs.push(input);
+
+    inputs
+}
+
+fn play(initial: World, seed: u64) {
+    // The same bot and calls as the game loop in main.rs, with a fixed rollout budget instead of a time limit
+    let mut config = AgentConfig::new();
+    config.max_rollouts_per_tick = ROLLOUTS_PER_TICK;
+    let mut bot = PoolBot::new(config, StdRng::seed_from_u64(seed));
+
+    let mut world = initial;
+    bot.start(&world);
+    for _ in 0..MAX_TICKS {
+        let action = bot.act(&world);
+        assert!(action.target.x.is_finite() && action.target.y.is_finite(), "Action {} is not finite", action);
+        bot.log(&world);
+
+        let events = simulator::next(&mut world, &action);
+        if events.iter().any(|event| event.is_ending()) || world.humans.is_empty() || world.zombies.is_empty() { break; }
+    }
+}
+
+#[test]
+fn game_loop_survives_edge_case_worlds() {
+    for (i, input) in edge_case_inputs().iter().enumerate() {
+        let world = parser::read_world_from(&mut input.as_bytes(), 0).unwrap_or_else(|error| panic!("Edge case {}: {}", i, error));
+        play(world, i as u64);
+    }
+}
+
+#[test]
+fn game_loop_survives_random_worlds() {
+    for seed in 0..NUM_GAMES {
+        let mut rng = StdRng::seed_from_u64(seed);
+        play(gen_world(&mut rng), seed);
+    }
+}
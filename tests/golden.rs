@@ -0,0 +1,15 @@
+#![cfg(feature = "golden")]
+
+use codevszombies::golden;
+
+#[test]
+fn scenario_scores_do_not_regress() {
+    let mut regressions = Vec::<String>::new();
+    for (path, golden_score) in golden::load(golden::GOLDEN_PATH) {
+        let score = golden::score(&path);
+        if score < golden_score {
+            regressions.push(format!("{}: {} < golden {}", path, score, golden_score));
+        }
+    }
+    assert!(regressions.is_empty(), "Scores regressed (re-bless with `cargo run --release -- bless scenarios/*.txt` if intended):\n{}", regressions.join("\n"));
+}
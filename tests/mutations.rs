@@ -0,0 +1,164 @@
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use codevszombies::model::*;
+use codevszombies::{mutations, rollouts};
+use codevszombies::mutations::OPERATORS;
+use codevszombies::config::AgentConfig;
+use codevszombies::evaluation::ScoreParams;
+
+mod common;
+use common::{gen_point, gen_world};
+
+const NUM_WORLDS: u64 = 200;
+const NUM_MUTATIONS: usize = 30;
+
+fn gen_id(rng: &mut StdRng) -> i32 {
+    rng.gen_range(0..25) // Often beyond the living humans and zombies
+}
+
+fn gen_milestone(rng: &mut StdRng) -> Milestone {
+    // Anything the notation can express, valid or not
+    match rng.gen_range(0..8) {
+        0 => Milestone::KillZombie { zombie_id: gen_id(rng) },
+        1 => Milestone::InterceptZombie { zombie_id: gen_id(rng) },
+        2 => Milestone::ComboAt { zombie_ids: (0..rng.gen_range(0..4)).map(|_| gen_id(rng)).collect() },
+        3 => Milestone::ProtectHuman { human_id: gen_id(rng), until: GuardCondition::ThreatCleared },
+        4 => Milestone::MoveTo { target: V2 { x: rng.gen_range(-2000.0..18000.0), y: rng.gen_range(-2000.0..11000.0) } },
+        5 => Milestone::MoveNear { anchor: Anchor::Zombie(gen_id(rng)), offset: gen_point(rng) },
+        6 => Milestone::MoveNear { anchor: Anchor::Centroid((0..rng.gen_range(0..4)).map(|_| gen_id(rng)).collect()), offset: V2::zero() },
+        _ => Milestone::Wait { ticks: rng.gen_range(1..10) },
+    }
+}
+
+fn gen_strategy(rng: &mut StdRng) -> Strategy {
+    let mut strategy = Strategy::new(0);
+    strategy.milestones = (0..rng.gen_range(0..15)).map(|_| gen_milestone(rng)).collect();
+    strategy
+}
+
+#[test]
+fn normalize_produces_valid_strategies() {
+    for seed in 0..NUM_WORLDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let world = gen_world(&mut rng);
+        let mut strategy = gen_strategy(&mut rng);
+
+        strategy.normalize(&world);
+        assert!(strategy.validate(&world).is_ok(), "{:?}", strategy.validate(&world));
+
+        // Normalizing twice changes nothing
+        let normalized = strategy.milestones.clone();
+        strategy.normalize(&world);
+        assert!(strategy.milestones == normalized, "Normalize is not idempotent: {}", strategy);
+    }
+}
+
+#[test]
+fn every_operator_output_is_normalized() {
+    for seed in 0..NUM_WORLDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let world = gen_world(&mut rng);
+        let mut strategy = Strategy::new(0);
+
+        for _ in 0..NUM_MUTATIONS {
+            for (operator_index, (name, _, _)) in OPERATORS.iter().enumerate() {
+                let mut candidate = strategy.clone();
+                if mutations::apply(operator_index, &mut candidate, &world, &mut rng) {
+                    assert!(candidate.validate(&world).is_ok(), "{}: {:?}", name, candidate.validate(&world));
+                }
+            }
+
+            // Carry on from a random operator's output so later mutations see longer strategies
+            mutations::apply(rng.gen_range(0..OPERATORS.len()), &mut strategy, &world, &mut rng);
+        }
+    }
+}
+
+fn assert_round_trips(strategy: &Strategy) {
+    let text = strategy.to_string();
+    let parsed = text.parse::<Strategy>().unwrap_or_else(|error| panic!("{} does not parse: {}", text, error));
+    assert!(parsed.id == strategy.id && parsed.milestones == strategy.milestones, "{} parses back as {}", text, parsed);
+}
+
+#[test]
+fn printed_strategies_parse_back_identically() {
+    for seed in 0..NUM_WORLDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let world = gen_world(&mut rng);
+        let mut strategy = Strategy::new(seed as i32);
+
+        for _ in 0..NUM_MUTATIONS {
+            for operator_index in 0..OPERATORS.len() {
+                let mut candidate = strategy.clone();
+                if mutations::apply(operator_index, &mut candidate, &world, &mut rng) {
+                    assert_round_trips(&candidate);
+                }
+            }
+            mutations::apply(rng.gen_range(0..OPERATORS.len()), &mut strategy, &world, &mut rng);
+        }
+    }
+
+    // Negative IDs and fractional values that operators do not happen to produce
+    assert_round_trips(&"[-3] h-1 h-2t4 z-2 c-1+2 @h-1(0.5,-3.25) @c-1+-2(0,0) (0.1,8999.9) wz2@1500.5".parse::<Strategy>().unwrap());
+}
+
+#[test]
+fn every_operator_can_change_the_strategy() {
+    let mut applied = vec![0; OPERATORS.len()];
+    let mut no_ops = vec![0; OPERATORS.len()];
+    for seed in 0..NUM_WORLDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let world = gen_world(&mut rng);
+        let mut strategy = Strategy::new(0);
+
+        for _ in 0..NUM_MUTATIONS {
+            for operator_index in 0..OPERATORS.len() {
+                let mut candidate = strategy.clone();
+                if mutations::apply(operator_index, &mut candidate, &world, &mut rng) {
+                    applied[operator_index] += 1;
+                    if candidate.milestones == strategy.milestones {
+                        no_ops[operator_index] += 1;
+                    }
+                }
+            }
+            mutations::apply(rng.gen_range(0..OPERATORS.len()), &mut strategy, &world, &mut rng);
+        }
+    }
+
+    for (i, (name, _, _)) in OPERATORS.iter().enumerate() {
+        eprintln!("{}: {} of {} applications were no-ops", name, no_ops[i], applied[i]);
+        assert!(applied[i] > 0, "{} never applied", name);
+        assert!(no_ops[i] < applied[i], "{} never changed anything", name);
+    }
+}
+
+#[test]
+fn simplified_strategy_plays_identically() {
+    let params = [ScoreParams::official(&AgentConfig::new())];
+    let mut num_stripped = 0;
+    for seed in 0..NUM_WORLDS {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let world = gen_world(&mut rng);
+        let mut strategy = gen_strategy(&mut rng);
+        strategy.normalize(&world);
+
+        let rollout = rollouts::rollout(strategy, &world, &params);
+        let simplified = rollouts::rollout(rollout.simplified(), &world, &params);
+        num_stripped += rollout.strategy.milestones.len() - simplified.strategy.milestones.len();
+
+        assert!(simplified.action_fingerprint == rollout.action_fingerprint, "{} plays differently from {}", simplified.strategy, rollout.strategy);
+        assert_eq!(simplified.events.len(), rollout.events.len());
+        assert!(simplified.effective.iter().all(|effective| *effective), "{} can be simplified further", simplified.strategy);
+    }
+    eprintln!("Simplification stripped {} milestones", num_stripped);
+    assert!(num_stripped > 0);
+}
+
+#[test]
+fn equal_strategies_share_a_fingerprint() {
+    let positive = "[0] (0,0) @z1(0,5) wz2@0".parse::<Strategy>().unwrap();
+    let negative = "[1] (-0,0) @z1(-0,5) wz2@-0".parse::<Strategy>().unwrap();
+    assert!(positive.milestones == negative.milestones);
+    assert_eq!(positive.fingerprint(), negative.fingerprint());
+}
@@ -0,0 +1,123 @@
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use codevszombies::model::*;
+use codevszombies::simulator;
+
+mod common;
+use common::{gen_point, gen_world};
+
+const NUM_GAMES: u64 = 300;
+const MAX_TICKS: i32 = 100;
+const ROUNDING: f32 = 1.5; // Positions are floored after each step, which can stretch a diagonal step slightly
+
+fn gen_action(world: &World, rng: &mut StdRng) -> Action {
+    // Mostly chase a zombie so that games end, otherwise wander
+    let zombies = world.zombies.values().collect::<Vec<&Zombie>>();
+    if !zombies.is_empty() && rng.gen::<f32>() < 0.7 {
+        Action { target: zombies[rng.gen_range(0..zombies.len())].next }
+    } else {
+        Action { target: gen_point(rng) }
+    }
+}
+
+// Plays random games, checking every tick of every game
+fn for_each_tick(check: impl Fn(&World, &Action, &World, &[Event])) {
+    for seed in 0..NUM_GAMES {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut world = gen_world(&mut rng);
+        for _ in 0..MAX_TICKS {
+            let action = gen_action(&world, &mut rng);
+            let before = world.clone();
+            let events = simulator::next(&mut world, &action);
+            check(&before, &action, &world, &events);
+
+            if events.iter().any(|event| event.is_ending()) { break; }
+        }
+    }
+}
+
+#[test]
+fn ash_moves_at_most_max_step() {
+    for_each_tick(|before, _, after, _| {
+        let distance = before.ash.pos.distance_to(after.ash.pos);
+        assert!(distance <= constants::MAX_ASH_STEP + ROUNDING, "Ash moved {}", distance);
+    });
+}
+
+#[test]
+fn zombies_move_at_most_max_step() {
+    for_each_tick(|before, _, after, _| {
+        for zombie in after.zombies.values() {
+            let previous = &before.zombies[&zombie.id];
+            let distance = previous.pos.distance_to(zombie.pos);
+            assert!(distance <= constants::MAX_ZOMBIE_STEP + ROUNDING, "Zombie {} moved {}", zombie.id, distance);
+
+            let planned = zombie.pos.distance_to(zombie.next);
+            assert!(planned <= constants::MAX_ZOMBIE_STEP + ROUNDING, "Zombie {} plans to move {}", zombie.id, planned);
+        }
+    });
+}
+
+#[test]
+fn entity_counts_never_increase() {
+    for_each_tick(|before, _, after, _| {
+        assert!(after.humans.len() <= before.humans.len());
+        assert!(after.zombies.len() <= before.zombies.len());
+        assert!(after.humans.keys().all(|id| before.humans.contains_key(id)));
+        assert!(after.zombies.keys().all(|id| before.zombies.contains_key(id)));
+    });
+}
+
+#[test]
+fn ending_is_emitted_once_and_only_when_a_side_is_empty() {
+    for_each_tick(|_, _, after, events| {
+        let endings = events.iter().filter(|event| event.is_ending()).collect::<Vec<&Event>>();
+        assert!(endings.len() <= 1, "{} endings in one tick", endings.len());
+
+        match endings.first() {
+            Some(Event::Won { num_humans, .. }) => {
+                assert!(after.zombies.is_empty() && !after.humans.is_empty());
+                assert_eq!(*num_humans, after.humans.len());
+            },
+            Some(Event::Lost { num_zombies, .. }) => {
+                assert!(after.humans.is_empty());
+                assert_eq!(*num_zombies, after.zombies.len());
+            },
+            _ => assert!(!after.humans.is_empty() && !after.zombies.is_empty(), "Game over without an ending"),
+        }
+    });
+}
+
+#[test]
+fn kill_multipliers_follow_fibonacci_sequence() {
+    for_each_tick(|before, _, _, events| {
+        let mut expected = (1.0, 2.0);
+        for event in events.iter() {
+            if let Event::ZombieKilled { score, multiplier, .. } = event {
+                assert_eq!(*multiplier, expected.0);
+                assert_eq!(*score, simulator::calculate_zombie_kill_score(before.humans.len() as i32));
+                expected = (expected.1, expected.0 + expected.1);
+            }
+        }
+    });
+}
+
+#[test]
+fn no_event_after_game_over() {
+    for seed in 0..NUM_GAMES {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut world = gen_world(&mut rng);
+        let mut is_over = false;
+        for _ in 0..MAX_TICKS {
+            let action = gen_action(&world, &mut rng);
+            let events = simulator::next(&mut world, &action);
+            if is_over {
+                assert!(events.is_empty(), "Events after the game was over");
+            } else if let Some(position) = events.iter().position(|event| event.is_ending()) {
+                assert_eq!(position, events.len() - 1, "Ending was not the last event of the tick");
+                is_over = true;
+            }
+        }
+    }
+}